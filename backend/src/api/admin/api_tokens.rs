@@ -0,0 +1,129 @@
+use crate::audit::{self, RequestMeta};
+use crate::auth::api_token::{self, ApiToken};
+use crate::auth::AdminUser;
+use crate::error::ApiError;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::{delete, get};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use shared::Problem;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateTokenRequest {
+    name: String,
+    /// Scope names such as `articles:write`.
+    #[serde(default)]
+    scopes: Vec<String>,
+    /// Never expires when absent.
+    expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedToken {
+    #[serde(flatten)]
+    details: ApiToken,
+    /// The secret; it can't be retrieved again.
+    token: String,
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list, create, revoke))]
+pub(super) struct ApiDoc;
+
+/// API token management for the signed-in user; mounted behind the admin guard.
+pub fn router() -> Router<crate::state::AppState> {
+    Router::new()
+        .route("/tokens", get(list).post(create))
+        .route("/tokens/:id", delete(revoke))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tokens",
+    tag = "tokens",
+    security(("bearer" = []), ("session" = [])),
+    responses((status = 200, description = "The caller's API tokens", body = [ApiToken]))
+)]
+async fn list(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+) -> Result<Json<Vec<ApiToken>>, ApiError> {
+    Ok(Json(api_token::list(&pool, user.id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/tokens",
+    tag = "tokens",
+    security(("bearer" = []), ("session" = [])),
+    request_body = CreateTokenRequest,
+    responses(
+        (status = 201, description = "The new token, with its secret", body = CreatedToken),
+        (status = 400, description = "Missing name, unknown scope or bad expiry", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn create(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Json(req): Json<CreateTokenRequest>,
+) -> Result<(StatusCode, Json<CreatedToken>), ApiError> {
+    user.require_interactive()?;
+
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Token name is required"));
+    }
+
+    let scopes = api_token::parse_scopes(user.role, &req.scopes).map_err(ApiError::bad_request)?;
+    let expires_at = api_token::expiry(req.expires_in_days).map_err(ApiError::bad_request)?;
+
+    let (details, token) = api_token::create(&pool, user.id, name, &scopes, expires_at).await?;
+
+    tracing::info!("User {} created API token {}", user.username, details.id);
+    audit::Entry::new("token.created")
+        .actor(user.id)
+        .target("api_token", details.id)
+        .details(serde_json::json!({ "name": name, "scopes": details.scopes }))
+        .record(&pool, &meta)
+        .await;
+    Ok((StatusCode::CREATED, Json(CreatedToken { details, token })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tokens/{id}",
+    tag = "tokens",
+    security(("bearer" = []), ("session" = [])),
+    params(("id" = Uuid, Path, description = "The token's id")),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The caller has no such token", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn revoke(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    user.require_interactive()?;
+
+    if !api_token::revoke(&pool, user.id, id).await? {
+        return Err(ApiError::not_found("Token not found"));
+    }
+    tracing::info!("User {} revoked API token {}", user.username, id);
+    audit::Entry::new("token.revoked")
+        .actor(user.id)
+        .target("api_token", id)
+        .record(&pool, &meta)
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,97 @@
+use crate::audit::{self, AuditRecord, Filter};
+use crate::auth::AdminUser;
+use crate::error::ApiError;
+use axum::extract::{Query, State};
+use axum::response::Json;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::{Permission, Problem};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    actor_id: Option<Uuid>,
+    /// e.g. `auth.login` or `user.created`.
+    action: Option<String>,
+    target_type: Option<String>,
+    target_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    /// `next_before` from the previous page.
+    before: Option<i64>,
+    /// Entries per page, 1 to 200; 50 when absent.
+    limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditPage {
+    entries: Vec<AuditRecord>,
+    /// Pass as `before` to fetch the next, older page; absent on the last page.
+    next_before: Option<i64>,
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list))]
+pub(super) struct ApiDoc;
+
+/// Read-only access to the audit log; requires `Permission::ViewAuditLog`.
+pub fn router() -> Router<crate::state::AppState> {
+    Router::new().route("/audit", get(list))
+}
+
+/// Treats `?action=` and friends as unset rather than matching the empty string.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "audit",
+    security(("bearer" = []), ("session" = [])),
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching entries, newest first", body = AuditPage),
+        (status = 403, description = "Needs the view_audit_log permission", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn list(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<AuditPage>, ApiError> {
+    user.require(Permission::ViewAuditLog)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let filter = Filter {
+        actor_id: query.actor_id,
+        action: non_empty(query.action),
+        target_type: non_empty(query.target_type),
+        target_id: non_empty(query.target_id),
+        since: query.since,
+        until: query.until,
+        before: query.before,
+    };
+
+    let entries = audit::search(&pool, &filter, limit).await?;
+
+    let next_before = if entries.len() as i64 == limit {
+        entries.last().map(|e| e.id)
+    } else {
+        None
+    };
+    Ok(Json(AuditPage {
+        entries,
+        next_before,
+    }))
+}
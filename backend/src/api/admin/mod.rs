@@ -0,0 +1,728 @@
+use axum::{
+    extract::{FromRequestParts, State},
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use axum::body::to_bytes;
+use axum::http::{header, Request, StatusCode};
+use axum::body::Body;
+use axum::response::Json;
+use axum::response::IntoResponse;
+use axum::response::Redirect;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use axum_extra::extract::cookie::CookieJar;
+use crate::auth::accounts;
+use crate::auth::keys::Keyring;
+use crate::auth::refresh::{self, Rotation};
+use crate::auth::session::{self, SessionConfig};
+use crate::audit::{self, RequestMeta};
+use crate::auth::throttle::{LoginThrottle, Scope};
+use crate::auth::totp;
+use crate::auth::{AdminUser, ACCESS_TOKEN_TTL_MINUTES};
+use crate::auth::password::{PasswordHashing, Verification};
+use crate::error::ApiError;
+use shared::Problem;
+use utoipa::{OpenApi, ToSchema};
+
+mod api_tokens;
+mod audit_log;
+mod passkeys;
+mod two_factor;
+mod users;
+
+#[derive(Deserialize, ToSchema)]
+pub struct LoginRequest {
+    username: String,
+    password: String,
+    /// JSON clients set this to get a session cookie instead of a bearer token.
+    #[serde(default)]
+    session: bool,
+    /// Where to send the browser after a successful login.
+    next: Option<String>,
+}
+
+#[derive(Serialize, Default, ToSchema)]
+pub struct LoginResponse {
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    tokens: Option<TokenPair>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redirect: Option<String>,
+    /// Set when the password was right but a TOTP code is still required;
+    /// post it with the code to `/admin/login/mfa`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mfa_token: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct MfaRequest {
+    code: String,
+    mfa_token: Option<String>,
+    #[serde(default)]
+    session: bool,
+    next: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TokenPair {
+    token: String,
+    refresh_token: String,
+    expires_in: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    refresh_token: String,
+}
+
+pub fn router(state: crate::state::AppState) -> Router<crate::state::AppState> {
+    Router::new()
+        .route("/me", get(me))
+        .route("/logout/all", post(logout_all))
+        .merge(api_tokens::router())
+        .merge(audit_log::router())
+        .merge(passkeys::router())
+        .merge(two_factor::router())
+        .merge(users::router())
+        // Every route registered above this line requires a valid admin token.
+        .route_layer(middleware::from_extractor_with_state::<AdminUser, _>(
+            state.clone(),
+        ))
+        .route("/login", post(login))
+        .route("/login/mfa", post(login_mfa))
+        .route("/login/passkey/start", post(passkeys::login_start))
+        .route("/login/passkey/finish", post(passkeys::login_finish))
+        .route("/logout", post(logout))
+        .route("/token/refresh", post(refresh_token))
+        .with_state(state)
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(me, logout_all, login, login_mfa, logout, refresh_token))]
+struct ApiDoc;
+
+/// The admin API's operations, with paths relative to `/admin`.
+pub(super) fn openapi() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(api_tokens::ApiDoc::openapi());
+    doc.merge(audit_log::ApiDoc::openapi());
+    doc.merge(passkeys::ApiDoc::openapi());
+    doc.merge(two_factor::ApiDoc::openapi());
+    doc.merge(users::ApiDoc::openapi());
+    doc
+}
+
+/// A login-form submission, decoded from JSON or a urlencoded form.
+struct Submission<T> {
+    body: T,
+    /// Browser form posts get redirects and cookies instead of JSON.
+    wants_html: bool,
+    meta: RequestMeta,
+}
+
+async fn read_submission<T: DeserializeOwned>(
+    req: Request<Body>,
+) -> Result<Submission<T>, ApiError> {
+    let (mut parts, body) = req.into_parts();
+    let meta = RequestMeta::from_request_parts(&mut parts, &()).await?;
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let accept = parts
+        .headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let is_form = content_type.contains("application/x-www-form-urlencoded")
+        || content_type.contains("multipart/form-data");
+    let bytes = to_bytes(body, 64 * 1024)
+        .await
+        .map_err(|_| ApiError::bad_request("Invalid request body"))?;
+
+    let body: T = if content_type.contains("application/json") {
+        serde_json::from_slice(&bytes).map_err(|_| ApiError::bad_request("Invalid JSON"))?
+    } else if is_form {
+        serde_urlencoded::from_bytes(&bytes)
+            .map_err(|_| ApiError::bad_request("Invalid form data"))?
+    } else {
+        return Err(ApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+            "Unsupported content type",
+        ));
+    };
+
+    Ok(Submission {
+        body,
+        wants_html: is_form || accept.contains("text/html"),
+        meta,
+    })
+}
+
+fn login_page_url(params: &[(&str, &str)]) -> String {
+    format!(
+        "/admin/login?{}",
+        serde_urlencoded::to_string(params).unwrap_or_default()
+    )
+}
+
+/// 429 with `Retry-After` for scripts, or back to the login form with a
+/// message for browsers.
+fn too_many_attempts<T>(
+    submission: &Submission<T>,
+    wait: chrono::Duration,
+    next: Option<&str>,
+) -> axum::response::Response {
+    let secs = wait.num_seconds().max(1) as u64;
+    if submission.wants_html {
+        let url = login_page_url(&[
+            ("error", "locked"),
+            ("retry_after", &secs.to_string()),
+            ("next", session::safe_next(next)),
+        ]);
+        return Redirect::to(&url).into_response();
+    }
+    ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        "too_many_attempts",
+        "Too many login attempts, try again later",
+    )
+    .retry_after(secs)
+    .into_response()
+}
+
+/// The audit entry for a completed sign-in.
+fn login_entry<T>(
+    user_id: sqlx::types::Uuid,
+    method: &str,
+    submission: &Submission<T>,
+    session_requested: bool,
+) -> audit::Entry {
+    let credential = if submission.wants_html || session_requested {
+        "session"
+    } else {
+        "token"
+    };
+    audit::Entry::new("auth.login")
+        .actor(user_id)
+        .target("user", user_id)
+        .details(serde_json::json!({ "method": method, "credential": credential }))
+}
+
+/// Records that failed sign-ins locked the client out. `username` is `None`
+/// for passkeys, where a rejected assertion names no account.
+async fn audit_lockout<T>(
+    pool: &PgPool,
+    submission: &Submission<T>,
+    username: Option<&str>,
+    wait: chrono::Duration,
+) {
+    audit::Entry::new("auth.locked_out")
+        .details(serde_json::json!({ "username": username, "seconds": wait.num_seconds() }))
+        .record(pool, &submission.meta)
+        .await;
+}
+
+/// Password sign-in. JSON clients get a token pair, or a session cookie with
+/// `session: true`; form posts get a cookie and a redirect to `next`. Accounts
+/// with TOTP get an `mfa_token` to finish at `/admin/login/mfa`.
+#[utoipa::path(
+    post,
+    path = "/login",
+    tag = "auth",
+    request_body(content(
+        (LoginRequest = "application/json"),
+        (LoginRequest = "application/x-www-form-urlencoded"),
+    )),
+    responses(
+        (status = 200, description = "Signed in, or a TOTP code is still needed", body = LoginResponse),
+        (status = 303, description = "Form posts: back to `next` or the login page"),
+        (status = 401, description = "Wrong username or password", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "Too many attempts; see `Retry-After`", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn login(
+    State(pool): State<PgPool>,
+    State(passwords): State<PasswordHashing>,
+    State(keys): State<Keyring>,
+    State(sessions): State<SessionConfig>,
+    State(throttle): State<LoginThrottle>,
+    req: Request<Body>,
+) -> Result<axum::response::Response, ApiError> {
+    let submission = read_submission::<LoginRequest>(req).await?;
+    let req = &submission.body;
+
+    let ip = submission.meta.ip.to_string();
+    let username_key = req.username.to_lowercase();
+    let throttle_keys = [(Scope::Ip, ip.as_str()), (Scope::Username, username_key.as_str())];
+    if let Some(wait) = throttle.check(&pool, &throttle_keys).await? {
+        return Ok(too_many_attempts(&submission, wait, req.next.as_deref()));
+    }
+
+    let user: Option<(sqlx::types::Uuid, String)> =
+        sqlx::query_as("SELECT id, password_hash FROM users WHERE username = $1 AND disabled_at IS NULL")
+            .bind(&req.username)
+            .fetch_optional(&pool)
+            .await?;
+
+    // Argon2 is deliberately slow, so keep it off the async workers.
+    let verification = {
+        let passwords = passwords.clone();
+        let stored = user.as_ref().map(|(_, hash)| hash.clone());
+        let password = req.password.clone();
+        tokio::task::spawn_blocking(move || match stored {
+            Some(stored) => passwords.verify(&stored, &password),
+            None => {
+                passwords.verify_dummy(&password);
+                Verification::Invalid
+            }
+        })
+        .await
+        .map_err(|_| ApiError::internal("Password check failed"))?
+    };
+
+    let user_id = match (verification, &user) {
+        (Verification::Invalid, _) | (_, None) => {
+            let mut entry = audit::Entry::new("auth.login_failed")
+                .details(serde_json::json!({ "method": "password", "username": req.username }));
+            if let Some((id, _)) = &user {
+                entry = entry.target("user", id);
+            }
+            entry.record(&pool, &submission.meta).await;
+
+            let lockout = throttle
+                .record_failure(&pool, &throttle_keys)
+                .await?;
+            if let Some(wait) = lockout {
+                audit_lockout(&pool, &submission, Some(&req.username), wait).await;
+                return Ok(too_many_attempts(&submission, wait, req.next.as_deref()));
+            }
+            if submission.wants_html {
+                let next = session::safe_next(req.next.as_deref());
+                let url = login_page_url(&[("error", "invalid"), ("next", next)]);
+                return Ok(Redirect::to(&url).into_response());
+            }
+
+            return Err(ApiError::unauthorized("Invalid credentials")
+                .with_code("invalid_credentials"));
+        }
+        (Verification::NeedsRehash, Some((id, _))) => {
+            let id = *id;
+            // Legacy plaintext rows and hashes with outdated cost parameters are
+            // upgraded transparently on the first successful login.
+            rehash_password(&pool, &passwords, id, req.password.clone()).await;
+            id
+        }
+        (Verification::Valid, Some((id, _))) => *id,
+    };
+
+    let mfa_enabled = totp::is_enabled(&pool, user_id).await?;
+
+    // With TOTP enabled the failure count is only cleared once the code passes.
+    if mfa_enabled {
+        let challenge = crate::auth::issue_mfa_challenge(&keys, user_id)
+            .map_err(|_| ApiError::internal("Token generation failed"))?;
+
+        if submission.wants_html {
+            let jar = CookieJar::new().add(sessions.mfa_cookie(challenge));
+            let next = req.next.as_deref().unwrap_or_default();
+            let url = login_page_url(&[("step", "mfa"), ("next", next)]);
+            return Ok((jar, Redirect::to(&url)).into_response());
+        }
+        return Ok(Json(LoginResponse {
+            mfa_token: Some(challenge),
+            ..Default::default()
+        })
+        .into_response());
+    }
+
+    throttle
+        .record_success(&pool, &username_key)
+        .await?;
+    complete_login(
+        &pool,
+        &keys,
+        &sessions,
+        user_id,
+        "password",
+        &submission,
+        req.session,
+        req.next.as_deref(),
+        CookieJar::new(),
+    )
+    .await
+}
+
+/// Second login step for accounts with TOTP enabled.
+#[utoipa::path(
+    post,
+    path = "/login/mfa",
+    tag = "auth",
+    request_body(content(
+        (MfaRequest = "application/json"),
+        (MfaRequest = "application/x-www-form-urlencoded"),
+    )),
+    responses(
+        (status = 200, description = "Signed in", body = LoginResponse),
+        (status = 303, description = "Form posts: back to `next` or the login page"),
+        (status = 401, description = "Wrong code or expired challenge", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "Too many attempts; see `Retry-After`", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn login_mfa(
+    State(pool): State<PgPool>,
+    State(keys): State<Keyring>,
+    State(sessions): State<SessionConfig>,
+    State(throttle): State<LoginThrottle>,
+    jar: CookieJar,
+    req: Request<Body>,
+) -> Result<axum::response::Response, ApiError> {
+    let submission = read_submission::<MfaRequest>(req).await?;
+    let req = &submission.body;
+
+    // JSON clients echo the challenge back; form posts carry it in a cookie.
+    let user_id = req
+        .mfa_token
+        .as_deref()
+        .or_else(|| jar.get(session::MFA_COOKIE_NAME).map(|c| c.value()))
+        .and_then(|challenge| crate::auth::verify_mfa_challenge(&keys, challenge));
+
+    let Some(user_id) = user_id else {
+        if submission.wants_html {
+            return Ok(Redirect::to("/admin/login?error=expired").into_response());
+        }
+        return Err(ApiError::unauthorized("Login challenge expired")
+            .with_code("challenge_expired"));
+    };
+
+    let username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await?;
+
+    // Codes are guessed against the same counters as passwords.
+    let ip = submission.meta.ip.to_string();
+    let username_key = username.to_lowercase();
+    let throttle_keys = [(Scope::Ip, ip.as_str()), (Scope::Username, username_key.as_str())];
+    if let Some(wait) = throttle.check(&pool, &throttle_keys).await? {
+        return Ok(too_many_attempts(&submission, wait, req.next.as_deref()));
+    }
+
+    let valid = totp::verify(&pool, user_id, &username, &req.code)
+        .await
+        .map_err(|e| {
+            tracing::error!("TOTP verification failed for {}: {}", user_id, e);
+            ApiError::internal("Verification failed")
+        })?;
+
+    if !valid {
+        audit::Entry::new("auth.login_failed")
+            .target("user", user_id)
+            .details(serde_json::json!({ "method": "totp", "username": username }))
+            .record(&pool, &submission.meta)
+            .await;
+
+        let lockout = throttle
+            .record_failure(&pool, &throttle_keys)
+            .await?;
+        if let Some(wait) = lockout {
+            audit_lockout(&pool, &submission, Some(&username), wait).await;
+            return Ok(too_many_attempts(&submission, wait, req.next.as_deref()));
+        }
+        if submission.wants_html {
+            let next = session::safe_next(req.next.as_deref());
+            let url = login_page_url(&[("step", "mfa"), ("error", "invalid_code"), ("next", next)]);
+            return Ok(Redirect::to(&url).into_response());
+        }
+        return Err(ApiError::unauthorized("Invalid code").with_code("invalid_code"));
+    }
+
+    throttle
+        .record_success(&pool, &username_key)
+        .await?;
+    complete_login(
+        &pool,
+        &keys,
+        &sessions,
+        user_id,
+        "totp",
+        &submission,
+        req.session,
+        req.next.as_deref(),
+        jar.remove(sessions.mfa_removal_cookie()),
+    )
+    .await
+}
+
+/// Hands out credentials once every required factor has been checked: a
+/// session cookie for browsers, or an access/refresh token pair for scripts.
+/// The sign-in is only audited as `auth.login` once those exist.
+#[allow(clippy::too_many_arguments)]
+async fn complete_login<T>(
+    pool: &PgPool,
+    keys: &Keyring,
+    sessions: &SessionConfig,
+    user_id: sqlx::types::Uuid,
+    method: &str,
+    submission: &Submission<T>,
+    session_requested: bool,
+    next: Option<&str>,
+    jar: CookieJar,
+) -> Result<axum::response::Response, ApiError> {
+    // Passkey and TOTP sign-ins arrive here with only a user id.
+    let active = accounts::is_active(pool, user_id).await?;
+    if !active {
+        audit::Entry::new("auth.login_failed")
+            .target("user", user_id)
+            .details(serde_json::json!({ "method": method, "reason": "account_disabled" }))
+            .record(pool, &submission.meta)
+            .await;
+        return Err(ApiError::forbidden("This account is disabled").with_code("account_disabled"));
+    }
+
+    let response = issue_credentials(
+        pool,
+        keys,
+        sessions,
+        user_id,
+        submission,
+        session_requested,
+        next,
+        jar,
+    )
+    .await?;
+    login_entry(user_id, method, submission, session_requested)
+        .record(pool, &submission.meta)
+        .await;
+    Ok(response)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn issue_credentials<T>(
+    pool: &PgPool,
+    keys: &Keyring,
+    sessions: &SessionConfig,
+    user_id: sqlx::types::Uuid,
+    submission: &Submission<T>,
+    session_requested: bool,
+    next: Option<&str>,
+    jar: CookieJar,
+) -> Result<axum::response::Response, ApiError> {
+    if !(submission.wants_html || session_requested) {
+        let tokens = issue_tokens(pool, keys, user_id).await?;
+        return Ok((
+            jar,
+            Json(LoginResponse {
+                tokens: Some(tokens),
+                ..Default::default()
+            }),
+        )
+            .into_response());
+    }
+
+    let value = session::create(pool, sessions, user_id, submission.meta.user_agent.as_deref())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create session for {}: {}", user_id, e);
+            ApiError::internal("Session creation failed")
+        })?;
+    let jar = jar.add(sessions.cookie(value));
+    let next = session::safe_next(next).to_string();
+
+    if submission.wants_html {
+        return Ok((jar, Redirect::to(&next)).into_response());
+    }
+    Ok((
+        jar,
+        Json(LoginResponse {
+            redirect: Some(next),
+            ..Default::default()
+        }),
+    )
+        .into_response())
+}
+
+async fn issue_tokens(
+    pool: &PgPool,
+    keys: &Keyring,
+    user_id: sqlx::types::Uuid,
+) -> Result<TokenPair, ApiError> {
+    let generation = refresh::token_generation(pool, user_id).await?;
+    let token = crate::auth::issue_access_token(keys, user_id, generation)
+        .map_err(|_| ApiError::internal("Token generation failed"))?;
+    let refresh_token = refresh::issue(pool, user_id).await?;
+
+    Ok(TokenPair {
+        token,
+        refresh_token,
+        expires_in: ACCESS_TOKEN_TTL_MINUTES * 60,
+    })
+}
+
+/// Trades a refresh token for a new pair; each refresh token works once.
+#[utoipa::path(
+    post,
+    path = "/token/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "A fresh token pair", body = TokenPair),
+        (status = 401, description = "Unknown, expired or reused refresh token", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn refresh_token(
+    State(pool): State<PgPool>,
+    State(keys): State<Keyring>,
+    meta: RequestMeta,
+    Json(req): Json<RefreshRequest>,
+) -> Result<Json<TokenPair>, ApiError> {
+    let rotation = refresh::rotate(&pool, &req.refresh_token).await?;
+
+    match rotation {
+        Rotation::Rotated {
+            user_id,
+            refresh_token,
+        } => {
+            let generation = refresh::token_generation(&pool, user_id).await?;
+            let token = crate::auth::issue_access_token(&keys, user_id, generation)
+                .map_err(|_| ApiError::internal("Token generation failed"))?;
+            audit::Entry::new("auth.token_refreshed")
+                .actor(user_id)
+                .record(&pool, &meta)
+                .await;
+            Ok(Json(TokenPair {
+                token,
+                refresh_token,
+                expires_in: ACCESS_TOKEN_TTL_MINUTES * 60,
+            }))
+        }
+        Rotation::Reused { user_id } => {
+            audit::Entry::new("auth.refresh_reused")
+                .target("user", user_id)
+                .record(&pool, &meta)
+                .await;
+            Err(invalid_refresh_token())
+        }
+        Rotation::Invalid => Err(invalid_refresh_token()),
+    }
+}
+
+fn invalid_refresh_token() -> ApiError {
+    ApiError::unauthorized("Invalid refresh token").with_code("invalid_refresh_token")
+}
+
+/// Ends the cookie session and revokes the refresh token in the body, if any.
+#[utoipa::path(
+    post,
+    path = "/logout",
+    tag = "auth",
+    request_body(content = Option<RefreshRequest>),
+    responses((status = 303, description = "Signed out; redirects to the login page"))
+)]
+async fn logout(
+    State(pool): State<PgPool>,
+    State(sessions): State<SessionConfig>,
+    user: Option<AdminUser>,
+    meta: RequestMeta,
+    jar: CookieJar,
+    body: Option<Json<RefreshRequest>>,
+) -> Result<impl IntoResponse, ApiError> {
+    if let Some(cookie) = jar.get(session::COOKIE_NAME) {
+        session::revoke(&pool, cookie.value()).await?;
+    }
+    if let Some(Json(req)) = body {
+        refresh::revoke(&pool, &req.refresh_token).await?;
+    }
+    if let Some(user) = user {
+        audit::Entry::new("auth.logout")
+            .actor(user.id)
+            .record(&pool, &meta)
+            .await;
+    }
+
+    Ok((
+        jar.remove(sessions.removal_cookie()),
+        Redirect::to("/admin/login"),
+    ))
+}
+
+/// Revokes every session, refresh token, API token and access token the
+/// caller holds.
+#[utoipa::path(
+    post,
+    path = "/logout/all",
+    tag = "auth",
+    security(("bearer" = []), ("session" = [])),
+    responses(
+        (status = 303, description = "Signed out everywhere; redirects to the login page"),
+        (status = 401, description = "Not signed in", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn logout_all(
+    State(pool): State<PgPool>,
+    State(sessions): State<SessionConfig>,
+    user: AdminUser,
+    meta: RequestMeta,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, ApiError> {
+    refresh::revoke_everything(&pool, user.id).await?;
+    tracing::info!("User {} logged out of all devices", user.username);
+    audit::Entry::new("auth.logout_all")
+        .actor(user.id)
+        .record(&pool, &meta)
+        .await;
+
+    Ok((
+        jar.remove(sessions.removal_cookie()),
+        Redirect::to("/admin/login"),
+    ))
+}
+
+async fn rehash_password(
+    pool: &PgPool,
+    passwords: &PasswordHashing,
+    user_id: sqlx::types::Uuid,
+    password: String,
+) {
+    let passwords = passwords.clone();
+    let hash = match tokio::task::spawn_blocking(move || passwords.hash(&password)).await {
+        Ok(Ok(hash)) => hash,
+        Ok(Err(e)) => {
+            tracing::error!("Failed to rehash password for {}: {}", user_id, e);
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Rehash task for {} panicked: {}", user_id, e);
+            return;
+        }
+    };
+
+    match sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+        .bind(hash)
+        .bind(user_id)
+        .execute(pool)
+        .await
+    {
+        Ok(_) => tracing::info!("Upgraded password hash for user {}", user_id),
+        Err(e) => tracing::error!("Failed to store rehashed password for {}: {}", user_id, e),
+    }
+}
+
+/// The signed-in user.
+#[utoipa::path(
+    get,
+    path = "/me",
+    tag = "auth",
+    security(("bearer" = []), ("session" = [])),
+    responses(
+        (status = 200, description = "The caller", body = AdminUser),
+        (status = 401, description = "Not signed in", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn me(user: AdminUser) -> Json<AdminUser> {
+    Json(user)
+}
@@ -0,0 +1,250 @@
+use super::{audit_lockout, complete_login, read_submission, too_many_attempts};
+use crate::audit::{self, RequestMeta};
+use crate::auth::keys::Keyring;
+use crate::auth::session::SessionConfig;
+use crate::auth::throttle::{LoginThrottle, Scope};
+use crate::auth::webauthn::{
+    self, AssertionCredential, Ceremony, Passkey, RegistrationCredential, WebauthnConfig,
+};
+use crate::auth::AdminUser;
+use crate::error::ApiError;
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{Request, StatusCode};
+use axum::response::Json;
+use axum::routing::{delete, get, post};
+use axum::Router;
+use axum_extra::extract::cookie::CookieJar;
+use serde::Deserialize;
+use shared::Problem;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegisterRequest {
+    challenge_id: Uuid,
+    /// Label shown in the passkey list, e.g. "Laptop".
+    name: Option<String>,
+    credential: RegistrationCredential,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PasskeyLoginRequest {
+    challenge_id: Uuid,
+    credential: AssertionCredential,
+    #[serde(default)]
+    session: bool,
+    next: Option<String>,
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list, remove, register_start, register_finish, login_start, login_finish))]
+pub(super) struct ApiDoc;
+
+/// Passkey management for the signed-in user; mounted behind the admin guard.
+pub fn router() -> Router<crate::state::AppState> {
+    Router::new()
+        .route("/passkeys", get(list))
+        .route("/passkeys/:id", delete(remove))
+        .route("/passkeys/register/start", post(register_start))
+        .route("/passkeys/register/finish", post(register_finish))
+}
+
+#[utoipa::path(
+    get,
+    path = "/passkeys",
+    tag = "passkeys",
+    security(("bearer" = []), ("session" = [])),
+    responses((status = 200, description = "The caller's passkeys", body = [Passkey]))
+)]
+async fn list(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+) -> Result<Json<Vec<Passkey>>, ApiError> {
+    Ok(Json(webauthn::list(&pool, user.id).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/passkeys/{id}",
+    tag = "passkeys",
+    security(("bearer" = []), ("session" = [])),
+    params(("id" = Uuid, Path, description = "The passkey's id")),
+    responses(
+        (status = 204, description = "Removed"),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The caller has no such passkey", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn remove(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    user.require_interactive()?;
+
+    if !webauthn::remove(&pool, user.id, id).await? {
+        return Err(ApiError::not_found("Passkey not found"));
+    }
+    tracing::info!("User {} removed passkey {}", user.username, id);
+    audit::Entry::new("account.passkey_removed")
+        .actor(user.id)
+        .target("passkey", id)
+        .record(&pool, &meta)
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/passkeys/register/start",
+    tag = "passkeys",
+    security(("bearer" = []), ("session" = [])),
+    responses(
+        (status = 200, description = "Options for `navigator.credentials.create`", body = Ceremony),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn register_start(
+    State(pool): State<PgPool>,
+    State(config): State<WebauthnConfig>,
+    user: AdminUser,
+) -> Result<Json<Ceremony>, ApiError> {
+    user.require_interactive()?;
+
+    let ceremony = webauthn::start_registration(&pool, &config, user.id, &user.username).await?;
+    Ok(Json(ceremony))
+}
+
+#[utoipa::path(
+    post,
+    path = "/passkeys/register/finish",
+    tag = "passkeys",
+    security(("bearer" = []), ("session" = [])),
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Registered"),
+        (status = 400, description = "The credential was not accepted", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn register_finish(
+    State(pool): State<PgPool>,
+    State(config): State<WebauthnConfig>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Json(req): Json<RegisterRequest>,
+) -> Result<StatusCode, ApiError> {
+    user.require_interactive()?;
+
+    let name = req
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or("Passkey");
+
+    let id = webauthn::finish_registration(&pool, &config, user.id, req.challenge_id, name, &req.credential)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to register passkey for {}: {}", user.id, e);
+            ApiError::internal("Registration failed")
+        })?
+        .ok_or_else(|| {
+            ApiError::bad_request("Passkey was not accepted").with_code("passkey_rejected")
+        })?;
+
+    tracing::info!("User {} registered a passkey", user.username);
+    audit::Entry::new("account.passkey_added")
+        .actor(user.id)
+        .target("passkey", id)
+        .details(serde_json::json!({ "name": name }))
+        .record(&pool, &meta)
+        .await;
+    Ok(StatusCode::CREATED)
+}
+
+/// Starts a passkey sign-in.
+#[utoipa::path(
+    post,
+    path = "/login/passkey/start",
+    tag = "auth",
+    responses((status = 200, description = "Options for `navigator.credentials.get`", body = Ceremony))
+)]
+pub(super) async fn login_start(
+    State(pool): State<PgPool>,
+    State(config): State<WebauthnConfig>,
+) -> Result<Json<Ceremony>, ApiError> {
+    Ok(Json(webauthn::start_authentication(&pool, &config).await?))
+}
+
+/// Signs in with a passkey. User verification on the authenticator stands in
+/// for both the password and the TOTP step.
+#[utoipa::path(
+    post,
+    path = "/login/passkey/finish",
+    tag = "auth",
+    request_body = PasskeyLoginRequest,
+    responses(
+        (status = 200, description = "Signed in", body = super::LoginResponse),
+        (status = 303, description = "Requests accepting `text/html`: on to `next`"),
+        (status = 401, description = "The passkey was not accepted", body = Problem, content_type = "application/problem+json"),
+        (status = 429, description = "Too many attempts; see `Retry-After`", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub(super) async fn login_finish(
+    State(pool): State<PgPool>,
+    State(keys): State<Keyring>,
+    State(sessions): State<SessionConfig>,
+    State(config): State<WebauthnConfig>,
+    State(throttle): State<LoginThrottle>,
+    req: Request<Body>,
+) -> Result<axum::response::Response, ApiError> {
+    let submission = read_submission::<PasskeyLoginRequest>(req).await?;
+    let req = &submission.body;
+
+    let ip = submission.meta.ip.to_string();
+    let throttle_keys = [(Scope::Ip, ip.as_str())];
+    if let Some(wait) = throttle.check(&pool, &throttle_keys).await? {
+        return Ok(too_many_attempts(&submission, wait, req.next.as_deref()));
+    }
+
+    let user_id = webauthn::finish_authentication(&pool, &config, req.challenge_id, &req.credential)
+        .await
+        .map_err(|e| {
+            tracing::error!("Passkey verification failed: {}", e);
+            ApiError::internal("Verification failed")
+        })?;
+
+    let Some(user_id) = user_id else {
+        audit::Entry::new("auth.login_failed")
+            .details(serde_json::json!({ "method": "passkey" }))
+            .record(&pool, &submission.meta)
+            .await;
+        let lockout = throttle
+            .record_failure(&pool, &throttle_keys)
+            .await?;
+        if let Some(wait) = lockout {
+            audit_lockout(&pool, &submission, None, wait).await;
+            return Ok(too_many_attempts(&submission, wait, req.next.as_deref()));
+        }
+        return Err(ApiError::unauthorized("Passkey not recognised")
+            .with_code("passkey_not_recognised"));
+    };
+
+    tracing::info!("User {} signed in with a passkey", user_id);
+    complete_login(
+        &pool,
+        &keys,
+        &sessions,
+        user_id,
+        "passkey",
+        &submission,
+        req.session,
+        req.next.as_deref(),
+        CookieJar::new(),
+    )
+    .await
+}
@@ -0,0 +1,172 @@
+use crate::audit::{self, RequestMeta};
+use crate::auth::totp::{self, Enrollment};
+use crate::auth::AdminUser;
+use crate::error::ApiError;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::{get, post};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use shared::Problem;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Serialize, ToSchema)]
+pub struct TwoFactorStatus {
+    enabled: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CodeRequest {
+    code: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RecoveryCodes {
+    recovery_codes: Vec<String>,
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(status, setup, enable, disable))]
+pub(super) struct ApiDoc;
+
+pub fn router() -> Router<crate::state::AppState> {
+    Router::new()
+        .route("/2fa", get(status))
+        .route("/2fa/setup", post(setup))
+        .route("/2fa/enable", post(enable))
+        .route("/2fa/disable", post(disable))
+}
+
+#[utoipa::path(
+    get,
+    path = "/2fa",
+    tag = "two-factor",
+    security(("bearer" = []), ("session" = [])),
+    responses((status = 200, description = "Whether TOTP is on for the caller", body = TwoFactorStatus))
+)]
+async fn status(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+) -> Result<Json<TwoFactorStatus>, ApiError> {
+    let enabled = totp::is_enabled(&pool, user.id).await?;
+    Ok(Json(TwoFactorStatus { enabled }))
+}
+
+/// Starts enrollment with a new secret. Nothing changes for login until the
+/// first code is confirmed through `/2fa/enable`.
+#[utoipa::path(
+    post,
+    path = "/2fa/setup",
+    tag = "two-factor",
+    security(("bearer" = []), ("session" = [])),
+    responses(
+        (status = 200, description = "A new secret and its QR code", body = Enrollment),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "Already enabled", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn setup(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+) -> Result<Json<Enrollment>, ApiError> {
+    user.require_interactive()?;
+
+    if totp::is_enabled(&pool, user.id).await? {
+        return Err(ApiError::conflict("Two-factor authentication is already enabled"));
+    }
+
+    totp::begin_enrollment(&pool, user.id, &user.username)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("Failed to start TOTP enrollment for {}: {}", user.id, e);
+            ApiError::internal("Enrollment failed")
+        })
+}
+
+/// Confirms enrollment with the first code and returns the recovery codes.
+/// They are only ever shown here.
+#[utoipa::path(
+    post,
+    path = "/2fa/enable",
+    tag = "two-factor",
+    security(("bearer" = []), ("session" = [])),
+    request_body = CodeRequest,
+    responses(
+        (status = 200, description = "Enabled; the one-time recovery codes", body = RecoveryCodes),
+        (status = 400, description = "Wrong code", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn enable(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Json(req): Json<CodeRequest>,
+) -> Result<Json<RecoveryCodes>, ApiError> {
+    user.require_interactive()?;
+
+    let codes = totp::confirm_enrollment(&pool, user.id, &user.username, req.code.trim())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to confirm TOTP enrollment for {}: {}", user.id, e);
+            ApiError::internal("Enrollment failed")
+        })?
+        .ok_or_else(invalid_code)?;
+
+    tracing::info!("User {} enabled two-factor authentication", user.username);
+    audit::Entry::new("account.2fa_enabled")
+        .actor(user.id)
+        .record(&pool, &meta)
+        .await;
+    Ok(Json(RecoveryCodes {
+        recovery_codes: codes,
+    }))
+}
+
+/// Requires a current code (or recovery code) so a hijacked session alone
+/// can't strip the second factor.
+#[utoipa::path(
+    post,
+    path = "/2fa/disable",
+    tag = "two-factor",
+    security(("bearer" = []), ("session" = [])),
+    request_body = CodeRequest,
+    responses(
+        (status = 204, description = "Disabled"),
+        (status = 400, description = "Wrong code", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "API tokens can't do this; sign in interactively", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn disable(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Json(req): Json<CodeRequest>,
+) -> Result<StatusCode, ApiError> {
+    user.require_interactive()?;
+
+    let valid = totp::verify(&pool, user.id, &user.username, &req.code)
+        .await
+        .map_err(|e| {
+            tracing::error!("TOTP verification failed for {}: {}", user.id, e);
+            ApiError::internal("Verification failed")
+        })?;
+    if !valid {
+        return Err(invalid_code());
+    }
+
+    totp::disable(&pool, user.id).await?;
+    tracing::info!("User {} disabled two-factor authentication", user.username);
+    audit::Entry::new("account.2fa_disabled")
+        .actor(user.id)
+        .record(&pool, &meta)
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn invalid_code() -> ApiError {
+    ApiError::bad_request("Invalid code").with_code("invalid_code")
+}
@@ -0,0 +1,231 @@
+use crate::audit::{self, RequestMeta};
+use crate::auth::accounts::{self, AccountChanges, UserSummary};
+use crate::auth::password::PasswordHashing;
+use crate::auth::AdminUser;
+use crate::error::ApiError;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::{get, patch};
+use axum::Router;
+use serde::Deserialize;
+use shared::{Permission, Problem, Role};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    username: String,
+    password: String,
+    role: Role,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateUserRequest {
+    role: Option<Role>,
+    /// Sets a new password and signs the user out everywhere.
+    password: Option<String>,
+    /// Disabling also signs the user out everywhere.
+    disabled: Option<bool>,
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list, create, update, remove))]
+pub(super) struct ApiDoc;
+
+/// User management; every handler requires `Permission::ManageUsers`, and
+/// only listing is open to API tokens.
+pub fn router() -> Router<crate::state::AppState> {
+    Router::new()
+        .route("/users", get(list).post(create))
+        .route("/users/:id", patch(update).delete(remove))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users",
+    tag = "users",
+    security(("bearer" = []), ("session" = [])),
+    responses(
+        (status = 200, description = "Every admin account", body = [UserSummary]),
+        (status = 403, description = "Needs the manage_users permission", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn list(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+) -> Result<Json<Vec<UserSummary>>, ApiError> {
+    user.require(Permission::ManageUsers)?;
+
+    Ok(Json(accounts::list(&pool).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    tag = "users",
+    security(("bearer" = []), ("session" = [])),
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, description = "The new account", body = UserSummary),
+        (status = 400, description = "Bad username or password too short", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Needs the manage_users permission; API tokens are refused", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The username is taken", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn create(
+    State(pool): State<PgPool>,
+    State(passwords): State<PasswordHashing>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<(StatusCode, Json<UserSummary>), ApiError> {
+    user.require(Permission::ManageUsers)?;
+    user.require_interactive()?;
+
+    let id = accounts::create(&pool, &passwords, &req.username, req.password, req.role).await?;
+    let created = accounts::fetch(&pool, id).await?;
+
+    tracing::info!(
+        "User {} created {} as {}",
+        user.username,
+        created.username,
+        req.role.as_str()
+    );
+    audit::Entry::new("user.created")
+        .actor(user.id)
+        .target("user", id)
+        .details(serde_json::json!({ "username": created.username, "role": req.role }))
+        .record(&pool, &meta)
+        .await;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/users/{id}",
+    tag = "users",
+    security(("bearer" = []), ("session" = [])),
+    params(("id" = Uuid, Path, description = "The user's id")),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "The updated account", body = UserSummary),
+        (status = 400, description = "Bad password, or disabling yourself", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Needs the manage_users permission; API tokens are refused", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "No such user", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn update(
+    State(pool): State<PgPool>,
+    State(passwords): State<PasswordHashing>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<Json<UserSummary>, ApiError> {
+    user.require(Permission::ManageUsers)?;
+    user.require_interactive()?;
+
+    if req.disabled == Some(true) && id == user.id {
+        return Err(ApiError::bad_request("You can't disable your own account"));
+    }
+
+    let password_reset = req.password.is_some();
+    let changes = AccountChanges {
+        role: req.role,
+        password: req.password,
+        disabled: req.disabled,
+    };
+    let (before, after) = accounts::update(&pool, &passwords, id, changes).await?;
+    tracing::info!("User {} updated user {}", user.username, id);
+
+    let mut details = audit::diff(
+        &serde_json::json!({ "role": before.role, "disabled": before.disabled }),
+        &serde_json::json!({ "role": after.role, "disabled": after.disabled }),
+    );
+    if password_reset {
+        details["password_reset"] = serde_json::Value::Bool(true);
+    }
+    audit::Entry::new("user.updated")
+        .actor(user.id)
+        .target("user", id)
+        .details(details)
+        .record(&pool, &meta)
+        .await;
+    Ok(Json(after))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/users/{id}",
+    tag = "users",
+    security(("bearer" = []), ("session" = [])),
+    params(("id" = Uuid, Path, description = "The user's id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 400, description = "Deleting yourself", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "Needs the manage_users permission; API tokens are refused", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "No such user", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn remove(
+    State(pool): State<PgPool>,
+    user: AdminUser,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    user.require(Permission::ManageUsers)?;
+    user.require_interactive()?;
+
+    if id == user.id {
+        return Err(ApiError::bad_request("You can't delete your own account"));
+    }
+
+    let username = accounts::delete(&pool, id).await?;
+
+    tracing::info!("User {} deleted user {}", user.username, id);
+    audit::Entry::new("user.deleted")
+        .actor(user.id)
+        .target("user", id)
+        .details(serde_json::json!({ "username": username }))
+        .record(&pool, &meta)
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    #[tokio::test]
+    async fn scoped_tokens_cannot_create_users() {
+        // Refused before the database is touched, so the pool never connects.
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let passwords = PasswordHashing::new(argon2::Params::new(8, 1, 1, None).unwrap()).unwrap();
+        let user = AdminUser {
+            id: Uuid::new_v4(),
+            username: "owner".to_string(),
+            role: Role::Owner,
+            scopes: Some(vec![Permission::ManageUsers]),
+        };
+        let req = CreateUserRequest {
+            username: "intruder".to_string(),
+            password: "correct horse battery staple".to_string(),
+            role: Role::Owner,
+        };
+
+        let err = create(
+            State(pool),
+            State(passwords),
+            user,
+            RequestMeta::cli(),
+            Json(req),
+        )
+        .await
+        .err()
+        .expect("an API token must not create users");
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,365 @@
+//! RSS 2.0, Atom and JSON Feed 1.1 for journalism, the blog and both
+//! combined, carrying full content. Item ids are the rows' UUIDs, so readers
+//! don't show an entry again when its slug or title changes.
+//!
+//! Responses carry an `ETag` and `Last-Modified` and answer conditional
+//! requests with `304 Not Modified`, since aggregators poll them constantly.
+
+use crate::cache;
+use crate::error::ApiError;
+use crate::site::SiteConfig;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::Response;
+use axum::{routing::get, Router};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::json;
+use sqlx::types::Uuid;
+use sqlx::{PgPool, Row};
+
+/// How many of the newest items each feed carries.
+const FEED_LIMIT: i64 = 50;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    All,
+    Journalism,
+    Blog,
+}
+
+impl Section {
+    /// Where the section's feeds live.
+    fn prefix(self) -> &'static str {
+        match self {
+            Section::All => "",
+            Section::Journalism => "/journalism",
+            Section::Blog => "/blog",
+        }
+    }
+
+    /// The page the feed mirrors.
+    fn page(self) -> &'static str {
+        match self {
+            Section::All => "/",
+            _ => self.prefix(),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Section::All => "Jake Wray",
+            Section::Journalism => "Jake Wray — Journalism",
+            Section::Blog => "Jake Wray — Blog",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Section::All => "Journalism and writing by Jake Wray.",
+            Section::Journalism => "News stories and features by Jake Wray.",
+            Section::Blog => "Jake Wray's personal blog.",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl Format {
+    fn file_name(self) -> &'static str {
+        match self {
+            Format::Rss => "feed.xml",
+            Format::Atom => "atom.xml",
+            Format::Json => "feed.json",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Format::Rss => "application/rss+xml; charset=utf-8",
+            Format::Atom => "application/atom+xml; charset=utf-8",
+            Format::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// `/feed.xml`, `/atom.xml` and `/feed.json`, and the same under
+/// `/journalism` and `/blog`.
+pub fn router(state: crate::state::AppState) -> Router<crate::state::AppState> {
+    let mut router = Router::new();
+    for section in [Section::All, Section::Journalism, Section::Blog] {
+        for format in [Format::Rss, Format::Atom, Format::Json] {
+            let path = format!("{}/{}", section.prefix(), format.file_name());
+            router = router.route(
+                &path,
+                get(
+                    move |State(pool): State<PgPool>,
+                          State(site): State<SiteConfig>,
+                          headers: HeaderMap| {
+                        feed(pool, site, headers, section, format)
+                    },
+                ),
+            );
+        }
+    }
+    router.with_state(state)
+}
+
+struct Item {
+    id: Uuid,
+    url: String,
+    title: String,
+    summary: Option<String>,
+    content_html: String,
+    author: String,
+    published_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+async fn feed(
+    pool: PgPool,
+    site: SiteConfig,
+    headers: HeaderMap,
+    section: Section,
+    format: Format,
+) -> Result<Response, ApiError> {
+    let items = load_items(&pool, &site, section).await?;
+    // A scheduled post changes the feed when it goes live, not when it was
+    // last edited, so both timestamps count.
+    let last_modified = items
+        .iter()
+        .map(|item| item.updated_at.max(item.published_at))
+        .max();
+
+    let body = match format {
+        Format::Rss => rss(&site, section, &items, last_modified),
+        Format::Atom => atom(&site, section, &items, last_modified),
+        Format::Json => json_feed(&site, section, &items),
+    };
+    Ok(cache::conditional(
+        &headers,
+        format.content_type(),
+        body,
+        last_modified,
+    ))
+}
+
+async fn load_items(
+    pool: &PgPool,
+    site: &SiteConfig,
+    section: Section,
+) -> Result<Vec<Item>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM (
+            SELECT 'article' AS kind, id, slug, title, excerpt AS summary, content, author,
+                   published_at, updated_at
+            FROM articles
+            WHERE $1
+            UNION ALL
+            SELECT 'blog_post', id, slug, title, NULL, content, 'Jake Wray', published_at, updated_at
+            FROM blog_posts
+            WHERE $2 AND published_at <= NOW()
+        ) items
+        ORDER BY published_at DESC, id DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(section != Section::Blog)
+    .bind(section != Section::Journalism)
+    .bind(FEED_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let slug: String = row.get("slug");
+            let content: String = row.get("content");
+            // Articles are stored as HTML; blog posts are Markdown.
+            let (url, content_html) = match row.get::<&str, _>("kind") {
+                "article" => (site.url(&format!("/journalism/{}", slug)), content),
+                _ => (
+                    site.url(&format!("/blog/{}", slug)),
+                    frontend::components::markdown::to_html(&content),
+                ),
+            };
+            Item {
+                id: row.get("id"),
+                url,
+                title: row.get("title"),
+                summary: row
+                    .get::<Option<String>, _>("summary")
+                    .filter(|s| !s.trim().is_empty()),
+                content_html,
+                author: row.get("author"),
+                published_at: row.get("published_at"),
+                updated_at: row.get("updated_at"),
+            }
+        })
+        .collect())
+}
+
+fn rss(
+    site: &SiteConfig,
+    section: Section,
+    items: &[Item],
+    last_modified: Option<DateTime<Utc>>,
+) -> String {
+    let mut out = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        "\n",
+        r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/">"#,
+        "\n<channel>\n"
+    ));
+    out += &format!("<title>{}</title>\n", escape(section.title()));
+    out += &format!("<link>{}</link>\n", escape(&site.url(section.page())));
+    out += &format!(
+        "<description>{}</description>\n",
+        escape(section.description())
+    );
+    out += "<language>en-ca</language>\n";
+    out += &format!(
+        "<atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape(&feed_url(site, section, Format::Rss))
+    );
+    if let Some(last_modified) = last_modified {
+        out += &format!(
+            "<lastBuildDate>{}</lastBuildDate>\n",
+            last_modified.to_rfc2822()
+        );
+    }
+    for item in items {
+        out += "<item>\n";
+        out += &format!("<title>{}</title>\n", escape(&item.title));
+        out += &format!("<link>{}</link>\n", escape(&item.url));
+        out += &format!("<guid isPermaLink=\"false\">urn:uuid:{}</guid>\n", item.id);
+        out += &format!("<pubDate>{}</pubDate>\n", item.published_at.to_rfc2822());
+        // RSS's own <author> must be an email address.
+        out += &format!("<dc:creator>{}</dc:creator>\n", escape(&item.author));
+        if let Some(summary) = &item.summary {
+            out += &format!("<description>{}</description>\n", escape(summary));
+        }
+        out += &format!(
+            "<content:encoded>{}</content:encoded>\n",
+            escape(&item.content_html)
+        );
+        out += "</item>\n";
+    }
+    out += "</channel>\n</rss>\n";
+    out
+}
+
+fn atom(
+    site: &SiteConfig,
+    section: Section,
+    items: &[Item],
+    last_modified: Option<DateTime<Utc>>,
+) -> String {
+    let self_url = feed_url(site, section, Format::Atom);
+    let mut out = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        "\n",
+        r#"<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en-ca">"#,
+        "\n"
+    ));
+    out += &format!("<id>{}</id>\n", escape(&self_url));
+    out += &format!("<title>{}</title>\n", escape(section.title()));
+    out += &format!("<subtitle>{}</subtitle>\n", escape(section.description()));
+    // Atom requires <updated> even when there is nothing in the feed.
+    out += &format!(
+        "<updated>{}</updated>\n",
+        rfc3339(last_modified.unwrap_or(DateTime::UNIX_EPOCH))
+    );
+    out += &format!("<link rel=\"self\" href=\"{}\"/>\n", escape(&self_url));
+    out += &format!(
+        "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n",
+        escape(&site.url(section.page()))
+    );
+    out += "<author><name>Jake Wray</name></author>\n";
+    for item in items {
+        out += "<entry>\n";
+        out += &format!("<id>urn:uuid:{}</id>\n", item.id);
+        out += &format!("<title>{}</title>\n", escape(&item.title));
+        out += &format!(
+            "<link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n",
+            escape(&item.url)
+        );
+        out += &format!("<published>{}</published>\n", rfc3339(item.published_at));
+        out += &format!("<updated>{}</updated>\n", rfc3339(item.updated_at));
+        out += &format!("<author><name>{}</name></author>\n", escape(&item.author));
+        if let Some(summary) = &item.summary {
+            out += &format!("<summary>{}</summary>\n", escape(summary));
+        }
+        out += &format!(
+            "<content type=\"html\">{}</content>\n",
+            escape(&item.content_html)
+        );
+        out += "</entry>\n";
+    }
+    out += "</feed>\n";
+    out
+}
+
+fn json_feed(site: &SiteConfig, section: Section, items: &[Item]) -> String {
+    let items: Vec<_> = items
+        .iter()
+        .map(|item| {
+            let mut value = json!({
+                "id": format!("urn:uuid:{}", item.id),
+                "url": item.url,
+                "title": item.title,
+                "content_html": item.content_html,
+                "date_published": rfc3339(item.published_at),
+                "date_modified": rfc3339(item.updated_at),
+                "authors": [{ "name": item.author }],
+            });
+            if let Some(summary) = &item.summary {
+                value["summary"] = json!(summary);
+            }
+            value
+        })
+        .collect();
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": section.title(),
+        "description": section.description(),
+        "home_page_url": site.url(section.page()),
+        "feed_url": feed_url(site, section, Format::Json),
+        "language": "en-CA",
+        "authors": [{ "name": "Jake Wray" }],
+        "items": items,
+    })
+    .to_string()
+}
+
+fn feed_url(site: &SiteConfig, section: Section, format: Format) -> String {
+    site.url(&format!("{}/{}", section.prefix(), format.file_name()))
+}
+
+fn rfc3339(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Escapes text for XML element content and attribute values.
+pub(super) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab and newlines aren't allowed
+            // anywhere in an XML document.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -1,10 +1,18 @@
 use axum::Router;
 
 mod admin;
+mod feeds;
+mod openapi;
+mod pagination;
 mod public;
+mod search;
+mod sitemap;
 
 pub fn router(state: crate::state::AppState) -> Router<crate::state::AppState> {
     Router::new()
         .merge(public::router(state.clone()))
+        .merge(feeds::router(state.clone()))
+        .merge(sitemap::router(state.clone()))
+        .merge(openapi::router())
         .nest("/admin", admin::router(state.clone()))
 }
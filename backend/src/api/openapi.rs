@@ -0,0 +1,152 @@
+//! The OpenAPI 3 document for the public and admin API, assembled from the
+//! `#[utoipa::path]` annotations next to each handler, and a Swagger UI
+//! for browsing it.
+//!
+//! Each router module exposes an `ApiDoc` listing its handlers, mirroring
+//! how its `router()` is mounted; the test below fails when the two disagree.
+
+use crate::auth::session;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "jakewray.ca API",
+        description = "Public content and the admin API. Errors are `application/problem+json` \
+            with a stable `code`. Cookie-authenticated requests other than GET need the \
+            `x-csrf-token` header."
+    ),
+    components(schemas(
+        shared::Article,
+        shared::BlogPost,
+        shared::CreativeWork,
+        shared::MediaItem,
+        shared::Project,
+        shared::Problem,
+    )),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "articles", description = "Journalism"),
+        (name = "blog"),
+        (name = "search"),
+        (name = "auth", description = "Signing in and out of the admin"),
+        (name = "users", description = "Admin accounts"),
+        (name = "tokens", description = "Personal API tokens"),
+        (name = "passkeys"),
+        (name = "two-factor"),
+        (name = "audit", description = "The admin audit log"),
+        (name = "meta"),
+    )
+)]
+struct ApiDoc;
+
+/// `bearer` takes an access token from `/admin/login` or an API token;
+/// `session` is the browser's login cookie.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+        components.add_security_scheme(
+            "session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(session::COOKIE_NAME))),
+        );
+    }
+}
+
+/// The whole document.
+pub fn spec() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(super::public::ApiDoc::openapi());
+    doc.merge(super::search::ApiDoc::openapi());
+    doc.nest("/admin", super::admin::openapi())
+}
+
+/// `/api/openapi.json` and the docs UI at `/api/docs`.
+pub fn router() -> SwaggerUi {
+    SwaggerUi::new("/api/docs").url("/api/openapi.json", spec())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    /// Every router source file and the prefix its routes are mounted under.
+    /// Each `.route("/path", get(..))` call must fit on one line.
+    const ROUTERS: &[(&str, &str)] = &[
+        ("", include_str!("public.rs")),
+        ("/admin", include_str!("admin/mod.rs")),
+        ("/admin", include_str!("admin/api_tokens.rs")),
+        ("/admin", include_str!("admin/audit_log.rs")),
+        ("/admin", include_str!("admin/passkeys.rs")),
+        ("/admin", include_str!("admin/two_factor.rs")),
+        ("/admin", include_str!("admin/users.rs")),
+    ];
+
+    const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+    /// `(method, path)` for each route, with `:param` written `{param}`.
+    fn routed() -> BTreeSet<(String, String)> {
+        let mut routes = BTreeSet::new();
+        for (prefix, source) in ROUTERS {
+            for line in source.lines() {
+                let Some(start) = line.find(".route(\"") else {
+                    continue;
+                };
+                let rest = &line[start + ".route(\"".len()..];
+                let (path, handlers) = rest.split_once('"').expect("unterminated route path");
+                let path: Vec<String> = path
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{}}}", param),
+                        None => segment.to_string(),
+                    })
+                    .collect();
+                let path = format!("{}{}", prefix, path.join("/"));
+                for method in METHODS {
+                    let called = handlers.match_indices(&format!("{}(", method)).any(|(i, _)| {
+                        !handlers[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == ':')
+                    });
+                    if called {
+                        routes.insert((method.to_string(), path.clone()));
+                    }
+                }
+            }
+        }
+        routes
+    }
+
+    fn documented() -> BTreeSet<(String, String)> {
+        let spec = serde_json::to_value(super::spec()).unwrap();
+        let mut operations = BTreeSet::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in METHODS {
+                if item.get(method).is_some() {
+                    operations.insert((method.to_string(), path.clone()));
+                }
+            }
+        }
+        operations
+    }
+
+    #[test]
+    fn spec_matches_routes() {
+        let routed = routed();
+        let documented = documented();
+        assert!(!routed.is_empty());
+        let undocumented: Vec<_> = routed.difference(&documented).collect();
+        let stale: Vec<_> = documented.difference(&routed).collect();
+        assert!(
+            undocumented.is_empty() && stale.is_empty(),
+            "routes missing from the OpenAPI spec: {:?}; documented but not routed: {:?}",
+            undocumented,
+            stale
+        );
+    }
+}
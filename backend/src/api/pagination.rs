@@ -0,0 +1,183 @@
+//! Keyset pagination for public listings ordered newest first by a
+//! `(timestamp, id)` pair, e.g. `(published_at, id)` for articles or
+//! `(created_at, id)` for media items and projects.
+//!
+//! Handlers take [`PageParams`] from the query string, resolve them into a
+//! [`PageRequest`], bind its `after_*` values and `fetch_limit()` into a
+//! query of the form
+//!
+//! ```sql
+//! WHERE ($1::timestamptz IS NULL OR (published_at, id) < ($1, $2))
+//! ORDER BY published_at DESC, id DESC
+//! LIMIT $3
+//! ```
+//!
+//! and hand the rows to [`Page::new`]. The response body stays a plain JSON
+//! array; the next page is advertised in a `Link: <…>; rel="next"` header
+//! and in `X-Next-Cursor`.
+
+use axum::http::header::{HeaderName, HeaderValue, LINK};
+use axum::http::Uri;
+use axum::response::{IntoResponse, Json, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+
+pub const DEFAULT_LIMIT: i64 = 20;
+pub const MAX_LIMIT: i64 = 100;
+
+pub static NEXT_CURSOR: HeaderName = HeaderName::from_static("x-next-cursor");
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Opaque value from a previous page's `X-Next-Cursor`.
+    pub cursor: Option<String>,
+    /// Items per page, 1 to 100; 20 when absent.
+    #[param(minimum = 1, maximum = 100)]
+    pub limit: Option<i64>,
+}
+
+/// Position of the last row a client has seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let raw = format!(
+            "{}/{}",
+            self.at.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.id
+        );
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(value).ok()?).ok()?;
+        let (at, id) = raw.split_once('/')?;
+        Some(Cursor {
+            at: DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc),
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Rows that can be paged through by their `(timestamp, id)` key.
+pub trait Keyed {
+    fn cursor(&self) -> Cursor;
+}
+
+impl Keyed for shared::Article {
+    fn cursor(&self) -> Cursor {
+        Cursor { at: self.published_at, id: self.id }
+    }
+}
+
+impl Keyed for shared::BlogPost {
+    fn cursor(&self) -> Cursor {
+        Cursor { at: self.published_at, id: self.id }
+    }
+}
+
+/// Validated [`PageParams`].
+pub struct PageRequest {
+    pub after: Option<Cursor>,
+    pub limit: i64,
+}
+
+impl PageRequest {
+    pub fn after_at(&self) -> Option<DateTime<Utc>> {
+        self.after.map(|c| c.at)
+    }
+
+    pub fn after_id(&self) -> Option<Uuid> {
+        self.after.map(|c| c.id)
+    }
+
+    /// One more row than the page holds, to learn whether another page follows.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+}
+
+impl TryFrom<&PageParams> for PageRequest {
+    type Error = ApiError;
+
+    fn try_from(params: &PageParams) -> Result<Self, Self::Error> {
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(ApiError::bad_request(format!(
+                "limit must be between 1 and {}",
+                MAX_LIMIT
+            ))
+            .with_code("invalid_limit"));
+        }
+        let after = match params.cursor.as_deref() {
+            None | Some("") => None,
+            Some(value) => Some(
+                Cursor::decode(value).ok_or_else(|| {
+                    ApiError::bad_request("Invalid cursor").with_code("invalid_cursor")
+                })?,
+            ),
+        };
+        Ok(PageRequest { after, limit })
+    }
+}
+
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<Cursor>,
+}
+
+impl<T: Keyed> Page<T> {
+    /// Builds a page from rows fetched with [`PageRequest::fetch_limit`].
+    pub fn new(mut rows: Vec<T>, request: &PageRequest) -> Self {
+        let limit = request.limit as usize;
+        let next = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(Keyed::cursor)
+        } else {
+            None
+        };
+        Page { items: rows, next }
+    }
+}
+
+impl<T: Serialize> Page<T> {
+    /// The items as a JSON array, with next-page headers when there is one.
+    /// `uri` is the request's own URI; its other parameters carry over into
+    /// the `Link` so filters stay applied.
+    pub fn into_response(self, uri: &Uri) -> Response {
+        let mut response = Json(self.items).into_response();
+        if let Some(next) = self.next {
+            let cursor = next.encode();
+            let headers = response.headers_mut();
+            if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"next\"", next_link(uri, &cursor))) {
+                headers.insert(LINK, link);
+            }
+            if let Ok(value) = HeaderValue::from_str(&cursor) {
+                headers.insert(NEXT_CURSOR.clone(), value);
+            }
+        }
+        response
+    }
+}
+
+fn next_link(uri: &Uri, cursor: &str) -> String {
+    let mut params: Vec<(String, String)> = uri
+        .query()
+        .and_then(|q| serde_urlencoded::from_str(q).ok())
+        .unwrap_or_default();
+    params.retain(|(key, _)| key != "cursor");
+    params.push(("cursor".to_string(), cursor.to_string()));
+    let query = serde_urlencoded::to_string(params).unwrap_or_default();
+    format!("{}?{}", uri.path(), query)
+}
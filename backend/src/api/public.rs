@@ -1,71 +1,249 @@
-use axum::{extract::State, routing::get, Json, Router};
-use shared::{Article, BlogPost};
+use crate::api::pagination::{Page, PageParams, PageRequest};
+use crate::error::ApiError;
+use axum::extract::{OriginalUri, Path, Query, State};
+use axum::response::Response;
+use axum::{routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use shared::{Article, BlogPost, Problem};
 use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router(state: crate::state::AppState) -> Router<crate::state::AppState> {
     Router::new()
         .route("/health", get(health_check))
         .route("/api/articles", get(list_articles))
+        .route("/api/articles/:slug", get(get_article))
+        .route("/api/blog", get(list_blog_posts))
+        .route("/api/blog/:slug", get(get_blog_post))
+        .route("/api/search", get(crate::api::search::search))
         .with_state(state)
 }
 
+/// Everything served by this module, for the OpenAPI document.
+#[derive(OpenApi)]
+#[openapi(paths(health_check, list_articles, get_article, list_blog_posts, get_blog_post))]
+pub(super) struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "meta",
+    responses((status = 200, description = "The server is up", body = String, example = "OK"))
+)]
 async fn health_check() -> &'static str {
     "OK"
 }
 
 use sqlx::Row;
 
-async fn list_articles(State(pool): State<PgPool>) -> Json<Vec<Article>> {
-    match sqlx::query("SELECT id, wp_id, slug, title, subtitle, excerpt, content, cover_image_url, author, published_at, origin FROM articles ORDER BY published_at DESC LIMIT 20")
-        .map(|row: sqlx::postgres::PgRow| {
-            let origin_str: String = row.get("origin");
-            let origin = match origin_str.as_str() {
-                "imported" => shared::Origin::Imported,
-                "synced" => shared::Origin::Synced,
-                _ => shared::Origin::Local,
-            };
-            Article {
-                id: row.get("id"),
-                wp_id: row.get("wp_id"),
-                slug: row.get("slug"),
-                title: row.get("title"),
-                subtitle: row.get("subtitle"),
-                excerpt: row.get("excerpt"),
-                content: row.get("content"),
-                cover_image_url: row.get("cover_image_url"),
-                author: row.get("author"),
-                published_at: row.get("published_at"),
-                origin,
-            }
-        })
-        .fetch_all(&pool)
-        .await
-    {
-        Ok(articles) => Json(articles),
-        Err(e) => {
-            tracing::error!("Failed to fetch articles: {}", e);
-            Json(Vec::new())
-        }
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ArticleQuery {
+    /// `imported`, `synced` or `local`.
+    origin: Option<String>,
+    /// Matched case-insensitively against the whole byline.
+    author: Option<String>,
+    /// Inclusive lower bound on `published_at`.
+    since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `published_at`.
+    until: Option<DateTime<Utc>>,
+}
+
+fn article_from_row(row: sqlx::postgres::PgRow) -> Article {
+    let origin = row
+        .get::<String, _>("origin")
+        .parse()
+        .unwrap_or(shared::Origin::Local);
+    Article {
+        id: row.get("id"),
+        wp_id: row.get("wp_id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        subtitle: row.get("subtitle"),
+        excerpt: row.get("excerpt"),
+        content: row.get("content"),
+        cover_image_url: row.get("cover_image_url"),
+        author: row.get("author"),
+        published_at: row.get("published_at"),
+        origin,
+        source_url: row.get("source_url"),
+        images: row.get("images"),
     }
 }
 
-async fn list_blog_posts(State(pool): State<PgPool>) -> Json<Vec<BlogPost>> {
-    match sqlx::query("SELECT id, slug, title, content, published_at, tags FROM blog_posts ORDER BY published_at DESC LIMIT 20")
-        .map(|row: sqlx::postgres::PgRow| BlogPost {
-            id: row.get("id"),
-            slug: row.get("slug"),
-            title: row.get("title"),
-            content: row.get("content"),
-            published_at: row.get("published_at"),
-            tags: row.get("tags"),
-        })
-        .fetch_all(&pool)
-        .await
-    {
-        Ok(posts) => Json(posts),
-        Err(e) => {
-            tracing::error!("Failed to fetch blog posts: {}", e);
-            Json(Vec::new())
+/// Articles newest first, a page at a time; see [`crate::api::pagination`].
+#[utoipa::path(
+    get,
+    path = "/api/articles",
+    tag = "articles",
+    params(PageParams, ArticleQuery),
+    responses(
+        (status = 200, description = "A page of articles", body = [Article], headers(
+            ("link" = String, description = "`<url>; rel=\"next\"` when another page follows"),
+            ("x-next-cursor" = String, description = "Cursor for the next page, when another page follows"),
+        )),
+        (status = 400, description = "Bad filter, limit or cursor", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn list_articles(
+    State(pool): State<PgPool>,
+    OriginalUri(uri): OriginalUri,
+    Query(page): Query<PageParams>,
+    Query(query): Query<ArticleQuery>,
+) -> Result<Response, ApiError> {
+    let page = PageRequest::try_from(&page)?;
+    let origin = query.origin.filter(|o| !o.is_empty());
+    if let Some(origin) = &origin {
+        if origin.parse::<shared::Origin>().is_err() {
+            return Err(ApiError::bad_request("origin must be imported, synced or local")
+                .with_code("invalid_origin"));
         }
     }
+    let author = query.author.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, wp_id, slug, title, subtitle, excerpt, content, cover_image_url, author, published_at, origin,
+               source_url, images
+        FROM articles
+        WHERE ($1::text IS NULL OR origin = $1)
+          AND ($2::text IS NULL OR lower(author) = lower($2))
+          AND ($3::timestamptz IS NULL OR published_at >= $3)
+          AND ($4::timestamptz IS NULL OR published_at < $4)
+          AND ($5::timestamptz IS NULL OR (published_at, id) < ($5, $6))
+        ORDER BY published_at DESC, id DESC
+        LIMIT $7
+        "#,
+    )
+    .bind(origin)
+    .bind(author)
+    .bind(query.since)
+    .bind(query.until)
+    .bind(page.after_at())
+    .bind(page.after_id())
+    .bind(page.fetch_limit())
+    .map(article_from_row)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Page::new(rows, &page).into_response(&uri))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/articles/{slug}",
+    tag = "articles",
+    params(("slug" = String, Path, description = "The article's slug")),
+    responses(
+        (status = 200, description = "The article", body = Article),
+        (status = 404, description = "No article has that slug", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn get_article(
+    State(pool): State<PgPool>,
+    Path(slug): Path<String>,
+) -> Result<Json<Article>, ApiError> {
+    sqlx::query(
+        r#"
+        SELECT id, wp_id, slug, title, subtitle, excerpt, content, cover_image_url, author, published_at, origin,
+               source_url, images
+        FROM articles
+        WHERE slug = $1
+        "#,
+    )
+    .bind(&slug)
+    .map(article_from_row)
+    .fetch_optional(&pool)
+    .await?
+    .map(Json)
+    .ok_or_else(|| ApiError::not_found("Article not found"))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BlogQuery {
+    /// Only posts carrying this tag.
+    tag: Option<String>,
+}
+
+fn blog_post_from_row(row: sqlx::postgres::PgRow) -> BlogPost {
+    BlogPost {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        content: row.get("content"),
+        published_at: row.get("published_at"),
+        tags: row.get("tags"),
+    }
+}
+
+/// Published posts, newest first, a page at a time; `?tag=` narrows to posts
+/// carrying that tag. Posts dated in the future stay hidden until then.
+#[utoipa::path(
+    get,
+    path = "/api/blog",
+    tag = "blog",
+    params(PageParams, BlogQuery),
+    responses(
+        (status = 200, description = "A page of published posts", body = [BlogPost], headers(
+            ("link" = String, description = "`<url>; rel=\"next\"` when another page follows"),
+            ("x-next-cursor" = String, description = "Cursor for the next page, when another page follows"),
+        )),
+        (status = 400, description = "Bad limit or cursor", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn list_blog_posts(
+    State(pool): State<PgPool>,
+    OriginalUri(uri): OriginalUri,
+    Query(page): Query<PageParams>,
+    Query(query): Query<BlogQuery>,
+) -> Result<Response, ApiError> {
+    let page = PageRequest::try_from(&page)?;
+    let tag = query.tag.filter(|t| !t.trim().is_empty());
+    let rows = sqlx::query(
+        r#"
+        SELECT id, slug, title, content, published_at, tags
+        FROM blog_posts
+        WHERE published_at <= NOW()
+          AND ($1::text IS NULL OR $1 = ANY(tags))
+          AND ($2::timestamptz IS NULL OR (published_at, id) < ($2, $3))
+        ORDER BY published_at DESC, id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(tag)
+    .bind(page.after_at())
+    .bind(page.after_id())
+    .bind(page.fetch_limit())
+    .map(blog_post_from_row)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Page::new(rows, &page).into_response(&uri))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blog/{slug}",
+    tag = "blog",
+    params(("slug" = String, Path, description = "The post's slug")),
+    responses(
+        (status = 200, description = "The post", body = BlogPost),
+        (status = 404, description = "No published post has that slug", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+async fn get_blog_post(
+    State(pool): State<PgPool>,
+    Path(slug): Path<String>,
+) -> Result<Json<BlogPost>, ApiError> {
+    sqlx::query(
+        "SELECT id, slug, title, content, published_at, tags FROM blog_posts WHERE slug = $1 AND published_at <= NOW()",
+    )
+    .bind(&slug)
+    .map(blog_post_from_row)
+    .fetch_optional(&pool)
+    .await?
+    .map(Json)
+    .ok_or_else(|| ApiError::not_found("Blog post not found"))
 }
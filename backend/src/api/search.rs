@@ -0,0 +1,143 @@
+//! Full-text search over articles, published blog posts and creative works,
+//! backed by the generated `search` columns and their GIN indexes.
+//!
+//! Queries use `websearch_to_tsquery`, so quoted phrases, `or` and `-word`
+//! work the way they do in web search engines.
+
+use crate::error::ApiError;
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::Deserialize;
+use shared::{Problem, SearchKind, SearchResult};
+use sqlx::{PgPool, Row};
+use utoipa::{IntoParams, OpenApi};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 50;
+const MAX_QUERY_LEN: usize = 200;
+
+/// Marks matched words in `ts_headline` output. Control characters can't
+/// occur in the stored text, so they survive escaping and are swapped for
+/// `<mark>` tags afterwards.
+const START_SEL: char = '\u{2}';
+const STOP_SEL: char = '\u{3}';
+
+#[derive(OpenApi)]
+#[openapi(paths(search))]
+pub(super) struct ApiDoc;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(super) struct SearchQuery {
+    /// Web-search syntax: `"exact phrase"`, `or`, `-excluded`.
+    #[param(required = true, max_length = 200)]
+    q: Option<String>,
+    /// Results to return, 1 to 50; 20 when absent.
+    #[param(minimum = 1, maximum = 50)]
+    limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    tag = "search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Matches, best first, with `<mark>`ed snippets", body = [SearchResult]),
+        (status = 400, description = "Missing or overlong query, or bad limit", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub(super) async fn search(
+    State(pool): State<PgPool>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchResult>>, ApiError> {
+    let q = query.q.as_deref().map(str::trim).unwrap_or_default();
+    if q.is_empty() {
+        return Err(ApiError::bad_request("q is required").with_code("missing_query"));
+    }
+    if q.len() > MAX_QUERY_LEN {
+        return Err(ApiError::bad_request(format!(
+            "q must be at most {} characters",
+            MAX_QUERY_LEN
+        ))
+        .with_code("query_too_long"));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ApiError::bad_request(format!(
+            "limit must be between 1 and {}",
+            MAX_LIMIT
+        ))
+        .with_code("invalid_limit"));
+    }
+
+    let headline_options = format!(
+        "StartSel={}, StopSel={}, MaxFragments=2, MaxWords=30, MinWords=12, FragmentDelimiter=\" … \"",
+        START_SEL, STOP_SEL
+    );
+
+    // Snippets are only built for the page of hits being returned;
+    // `ts_headline` reparses the whole body, so it's the expensive part.
+    let rows = sqlx::query(
+        r#"
+        WITH q AS (SELECT websearch_to_tsquery('english', $1) AS query),
+        hits AS (
+            SELECT 'article' AS kind, slug, title, content AS body, published_at,
+                   ts_rank_cd(search, q.query) AS rank
+            FROM articles, q
+            WHERE search @@ q.query
+            UNION ALL
+            SELECT 'blog_post', slug, title, content, published_at, ts_rank_cd(search, q.query)
+            FROM blog_posts, q
+            WHERE search @@ q.query AND published_at <= NOW()
+            UNION ALL
+            SELECT 'creative_work', slug, title, coalesce(content, synopsis, ''), published_at,
+                   ts_rank_cd(search, q.query)
+            FROM creative_works, q
+            WHERE search @@ q.query AND status = 'published'
+            ORDER BY rank DESC, published_at DESC NULLS LAST
+            LIMIT $2
+        )
+        SELECT kind, slug, title, published_at, rank,
+               ts_headline('english', regexp_replace(body, '<[^>]*>', ' ', 'g'), q.query, $3) AS snippet
+        FROM hits, q
+        ORDER BY rank DESC, published_at DESC NULLS LAST
+        "#,
+    )
+    .bind(q)
+    .bind(limit)
+    .bind(headline_options)
+    .fetch_all(&pool)
+    .await?;
+
+    let results = rows
+        .into_iter()
+        .map(|row| {
+            let kind = match row.get::<&str, _>("kind") {
+                "article" => SearchKind::Article,
+                "blog_post" => SearchKind::BlogPost,
+                _ => SearchKind::CreativeWork,
+            };
+            SearchResult {
+                kind,
+                slug: row.get("slug"),
+                title: row.get("title"),
+                snippet: highlight(row.get("snippet")),
+                published_at: row.get("published_at"),
+                rank: row.get("rank"),
+            }
+        })
+        .collect();
+    Ok(Json(results))
+}
+
+/// Makes a `ts_headline` fragment safe to render as HTML. Angle brackets left
+/// over from Markdown or stray markup are escaped; entities such as `&#8217;`
+/// from imported articles are kept so they display as intended.
+fn highlight(snippet: &str) -> String {
+    snippet
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(START_SEL, "<mark>")
+        .replace(STOP_SEL, "</mark>")
+}
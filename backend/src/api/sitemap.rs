@@ -0,0 +1,166 @@
+//! `robots.txt` and the sitemap: the app's static pages plus every article
+//! and published blog post. Past the protocol's 50,000 URL limit,
+//! `/sitemap.xml` becomes an index of `/sitemaps/<n>.xml` files.
+//!
+//! Creative works and projects are left out until the app has pages for
+//! them; listing their slugs now would only point crawlers at 404s. Adding
+//! them will also need `creative_works.updated_at` and `projects.slug`,
+//! which the schema doesn't have yet.
+
+use super::feeds::escape;
+use crate::error::ApiError;
+use crate::site::SiteConfig;
+use axum::extract::{Path, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{routing::get, Router};
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::{PgPool, Row};
+
+/// The most URLs one sitemap file may list.
+const MAX_URLS: usize = 50_000;
+
+pub fn router(state: crate::state::AppState) -> Router<crate::state::AppState> {
+    Router::new()
+        .route("/robots.txt", get(robots))
+        .route("/sitemap.xml", get(sitemap))
+        .route("/sitemaps/:file", get(sitemap_part))
+        .with_state(state)
+}
+
+async fn robots(State(site): State<SiteConfig>) -> impl IntoResponse {
+    let mut out = String::from("User-agent: *\n");
+    for path in &site.disallow {
+        out += &format!("Disallow: {}\n", path);
+    }
+    out += &format!("\nSitemap: {}\n", site.url("/sitemap.xml"));
+    ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], out)
+}
+
+struct Entry {
+    path: String,
+    lastmod: Option<DateTime<Utc>>,
+}
+
+async fn sitemap(
+    State(pool): State<PgPool>,
+    State(site): State<SiteConfig>,
+) -> Result<Response, ApiError> {
+    let entries = load_entries(&pool, &site).await?;
+    if entries.len() <= MAX_URLS {
+        return Ok(xml(urlset(&site, &entries)));
+    }
+
+    let mut out = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        "\n",
+        r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        "\n"
+    ));
+    for (i, chunk) in entries.chunks(MAX_URLS).enumerate() {
+        out += "<sitemap>";
+        out += &format!(
+            "<loc>{}</loc>",
+            escape(&site.url(&format!("/sitemaps/{}.xml", i + 1)))
+        );
+        if let Some(lastmod) = chunk.iter().filter_map(|e| e.lastmod).max() {
+            out += &format!("<lastmod>{}</lastmod>", w3c_date(lastmod));
+        }
+        out += "</sitemap>\n";
+    }
+    out += "</sitemapindex>\n";
+    Ok(xml(out))
+}
+
+/// One file of a sitemap index, numbered from 1.
+async fn sitemap_part(
+    State(pool): State<PgPool>,
+    State(site): State<SiteConfig>,
+    Path(file): Path<String>,
+) -> Result<Response, ApiError> {
+    let not_found = || ApiError::not_found("No such sitemap");
+    let n: usize = file
+        .strip_suffix(".xml")
+        .and_then(|n| n.parse().ok())
+        .filter(|n| *n >= 1)
+        .ok_or_else(not_found)?;
+    let entries = load_entries(&pool, &site).await?;
+    // A lone sitemap is only served at /sitemap.xml.
+    if entries.len() <= MAX_URLS {
+        return Err(not_found());
+    }
+    let chunk = entries.chunks(MAX_URLS).nth(n - 1).ok_or_else(not_found)?;
+    Ok(xml(urlset(&site, chunk)))
+}
+
+/// Static pages first, then content. A section page's `lastmod` is that of
+/// the newest item under it.
+async fn load_entries(pool: &PgPool, site: &SiteConfig) -> Result<Vec<Entry>, ApiError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT '/journalism/' || slug AS path, updated_at AS lastmod
+        FROM articles
+        UNION ALL
+        SELECT '/blog/' || slug, GREATEST(updated_at, published_at)
+        FROM blog_posts
+        WHERE published_at <= NOW()
+        ORDER BY path
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    let content: Vec<Entry> = rows
+        .into_iter()
+        .map(|row| Entry {
+            path: row.get("path"),
+            lastmod: row.get("lastmod"),
+        })
+        .filter(|entry| site.allows(&entry.path))
+        .collect();
+
+    let mut entries: Vec<Entry> = site
+        .pages
+        .iter()
+        .map(|page| {
+            let prefix = format!("{}/", page.trim_end_matches('/'));
+            let lastmod = content
+                .iter()
+                .filter(|entry| entry.path.starts_with(&prefix))
+                .filter_map(|entry| entry.lastmod)
+                .max();
+            Entry {
+                path: page.clone(),
+                lastmod,
+            }
+        })
+        .collect();
+    entries.extend(content);
+    Ok(entries)
+}
+
+fn urlset(site: &SiteConfig, entries: &[Entry]) -> String {
+    let mut out = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+        "\n",
+        r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        "\n"
+    ));
+    for entry in entries {
+        out += "<url>";
+        out += &format!("<loc>{}</loc>", escape(&site.url(&entry.path)));
+        if let Some(lastmod) = entry.lastmod {
+            out += &format!("<lastmod>{}</lastmod>", w3c_date(lastmod));
+        }
+        out += "</url>\n";
+    }
+    out += "</urlset>\n";
+    out
+}
+
+fn xml(body: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], body).into_response()
+}
+
+fn w3c_date(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
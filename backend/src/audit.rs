@@ -0,0 +1,200 @@
+use crate::auth::throttle::ClientIp;
+use crate::error::ApiError;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::header;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Where a request came from, for audit entries and throttling.
+#[derive(Debug, Clone)]
+pub struct RequestMeta {
+    pub ip: IpAddr,
+    pub user_agent: Option<String>,
+}
+
+impl RequestMeta {
+    /// For actions taken with the `admin` CLI rather than over HTTP.
+    pub fn cli() -> Self {
+        RequestMeta {
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            user_agent: Some("admin-cli".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestMeta
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        Ok(RequestMeta { ip, user_agent })
+    }
+}
+
+/// One audit log entry, written with [`Entry::record`].
+///
+/// `action` is a dotted name such as `auth.login` or `user.updated`; the full
+/// list is in `docs/AUTH_IMPLEMENTATION.md`.
+#[must_use = "call .record() to write the entry"]
+pub struct Entry {
+    action: &'static str,
+    actor_id: Option<Uuid>,
+    target: Option<(&'static str, String)>,
+    details: Value,
+}
+
+impl Entry {
+    pub fn new(action: &'static str) -> Self {
+        Self {
+            action,
+            actor_id: None,
+            target: None,
+            details: json!({}),
+        }
+    }
+
+    /// The user who acted. Their current username is stored alongside.
+    pub fn actor(mut self, user_id: Uuid) -> Self {
+        self.actor_id = Some(user_id);
+        self
+    }
+
+    pub fn target(mut self, kind: &'static str, id: impl ToString) -> Self {
+        self.target = Some((kind, id.to_string()));
+        self
+    }
+
+    pub fn details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Appends the entry. Failures are logged rather than returned so a
+    /// broken audit insert never turns a completed action into an error.
+    pub async fn record(self, pool: &PgPool, meta: &RequestMeta) {
+        let (target_type, target_id) = self.target.unzip();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO audit_log (actor_id, actor_username, action, target_type, target_id, ip, user_agent, details)
+            VALUES ($1, (SELECT username FROM users WHERE id = $1), $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(self.actor_id)
+        .bind(self.action)
+        .bind(target_type)
+        .bind(target_id)
+        .bind(meta.ip.to_string())
+        .bind(meta.user_agent.as_deref())
+        .bind(&self.details)
+        .execute(pool)
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Failed to write audit entry {}: {}", self.action, e);
+        }
+    }
+}
+
+/// The fields that differ between two JSON objects, as
+/// `{ "field": { "from": old, "to": new } }`.
+pub fn diff(before: &Value, after: &Value) -> Value {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let changes: Map<String, Value> = before
+        .keys()
+        .chain(after.keys().filter(|k| !before.contains_key(*k)))
+        .filter_map(|key| {
+            let from = before.get(key).unwrap_or(&Value::Null);
+            let to = after.get(key).unwrap_or(&Value::Null);
+            (from != to).then(|| (key.clone(), json!({ "from": from, "to": to })))
+        })
+        .collect();
+    Value::Object(changes)
+}
+
+#[derive(Serialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: Value,
+}
+
+/// Filters for [`search`]; unset fields match everything.
+#[derive(Default)]
+pub struct Filter {
+    pub actor_id: Option<Uuid>,
+    /// An exact action, or a prefix ending in `.` such as `auth.`.
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries older than this id, for paging backwards.
+    pub before: Option<i64>,
+}
+
+/// Newest entries first.
+pub async fn search(
+    pool: &PgPool,
+    filter: &Filter,
+    limit: i64,
+) -> Result<Vec<AuditRecord>, sqlx::Error> {
+    let action_pattern = filter.action.as_deref().map(|a| {
+        let escaped = a.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        if escaped.ends_with('.') {
+            format!("{}%", escaped)
+        } else {
+            escaped
+        }
+    });
+
+    sqlx::query_as(
+        r#"
+        SELECT id, occurred_at, actor_id, actor_username, action, target_type, target_id, ip, user_agent, details
+        FROM audit_log
+        WHERE ($1::uuid IS NULL OR actor_id = $1)
+          AND ($2::text IS NULL OR action LIKE $2)
+          AND ($3::text IS NULL OR target_type = $3)
+          AND ($4::text IS NULL OR target_id = $4)
+          AND ($5::timestamptz IS NULL OR occurred_at >= $5)
+          AND ($6::timestamptz IS NULL OR occurred_at < $6)
+          AND ($7::bigint IS NULL OR id < $7)
+        ORDER BY id DESC
+        LIMIT $8
+        "#,
+    )
+    .bind(filter.actor_id)
+    .bind(action_pattern)
+    .bind(filter.target_type.as_deref())
+    .bind(filter.target_id.as_deref())
+    .bind(filter.since)
+    .bind(filter.until)
+    .bind(filter.before)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,283 @@
+use crate::auth::password::PasswordHashing;
+use crate::auth::refresh;
+use crate::error::ApiError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use shared::Role;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use std::fmt;
+
+/// Account rules shared by the admin API and the `admin` CLI, so the two
+/// can't drift apart.
+pub const MIN_PASSWORD_LENGTH: usize = 12;
+
+#[derive(Serialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct UserSummary {
+    pub id: Uuid,
+    pub username: String,
+    #[sqlx(try_from = "String")]
+    pub role: Role,
+    pub two_factor: bool,
+    pub disabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub enum AccountError {
+    /// The request breaks a rule; the message says which.
+    Invalid(String),
+    /// It would conflict with existing data, e.g. a taken username.
+    Conflict(String),
+    NotFound,
+    Hashing(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for AccountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountError::Invalid(msg) | AccountError::Conflict(msg) => f.write_str(msg),
+            AccountError::NotFound => f.write_str("User not found"),
+            AccountError::Hashing(e) => write!(f, "Password hashing failed: {}", e),
+            AccountError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AccountError {
+    fn from(e: sqlx::Error) -> Self {
+        AccountError::Database(e)
+    }
+}
+
+impl From<AccountError> for ApiError {
+    fn from(err: AccountError) -> Self {
+        match err {
+            AccountError::Invalid(msg) => ApiError::bad_request(msg),
+            AccountError::Conflict(msg) => ApiError::conflict(msg),
+            AccountError::NotFound => ApiError::not_found(err.to_string()),
+            AccountError::Hashing(_) => {
+                tracing::error!("{}", err);
+                ApiError::internal("Password hashing failed")
+            }
+            AccountError::Database(e) => ApiError::from(e),
+        }
+    }
+}
+
+const SELECT_USERS: &str = r#"
+    SELECT id, username, role, totp_enabled_at IS NOT NULL AS two_factor,
+           disabled_at IS NOT NULL AS disabled, created_at
+    FROM users
+"#;
+
+pub async fn list(pool: &PgPool) -> Result<Vec<UserSummary>, AccountError> {
+    Ok(sqlx::query_as(&format!("{} ORDER BY username", SELECT_USERS))
+        .fetch_all(pool)
+        .await?)
+}
+
+pub async fn fetch(pool: &PgPool, id: Uuid) -> Result<UserSummary, AccountError> {
+    sqlx::query_as(&format!("{} WHERE id = $1", SELECT_USERS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AccountError::NotFound)
+}
+
+pub async fn find_by_username(pool: &PgPool, username: &str) -> Result<UserSummary, AccountError> {
+    sqlx::query_as(&format!("{} WHERE username = $1", SELECT_USERS))
+        .bind(username)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AccountError::NotFound)
+}
+
+/// False for disabled or deleted accounts, which must not be signed in.
+pub async fn is_active(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let active: Option<bool> =
+        sqlx::query_scalar("SELECT disabled_at IS NULL FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(active.unwrap_or(false))
+}
+
+pub async fn create(
+    pool: &PgPool,
+    passwords: &PasswordHashing,
+    username: &str,
+    password: String,
+    role: Role,
+) -> Result<Uuid, AccountError> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err(AccountError::Invalid("Username is required".to_string()));
+    }
+    let hash = hash_password(passwords, password).await?;
+
+    sqlx::query_scalar(
+        "INSERT INTO users (username, password_hash, role) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(username)
+    .bind(hash)
+    .bind(role.as_str())
+    .fetch_one(pool)
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db) if db.is_unique_violation() => {
+            AccountError::Conflict("Username is already taken".to_string())
+        }
+        _ => AccountError::Database(e),
+    })
+}
+
+/// Changes to an account that are applied together by [`update`].
+#[derive(Default)]
+pub struct AccountChanges {
+    pub role: Option<Role>,
+    pub password: Option<String>,
+    pub disabled: Option<bool>,
+}
+
+/// Applies `changes` all or nothing, returning the account before and after.
+/// The password is checked and hashed before anything is written; a new
+/// password or disabling the account also signs the user out everywhere.
+pub async fn update(
+    pool: &PgPool,
+    passwords: &PasswordHashing,
+    id: Uuid,
+    changes: AccountChanges,
+) -> Result<(UserSummary, UserSummary), AccountError> {
+    let hash = match changes.password {
+        Some(password) => Some(hash_password(passwords, password).await?),
+        None => None,
+    };
+
+    let mut tx = pool.begin().await?;
+    if changes.role.is_some_and(|role| role != Role::Owner) || changes.disabled == Some(true) {
+        ensure_other_owner(&mut tx, id).await?;
+    }
+    let before: UserSummary = sqlx::query_as(&format!("{} WHERE id = $1 FOR UPDATE", SELECT_USERS))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AccountError::NotFound)?;
+
+    if let Some(role) = changes.role {
+        sqlx::query("UPDATE users SET role = $1 WHERE id = $2")
+            .bind(role.as_str())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    if let Some(hash) = &hash {
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+            .bind(hash)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    if let Some(disabled) = changes.disabled {
+        sqlx::query(
+            "UPDATE users SET disabled_at = CASE WHEN $1 THEN COALESCE(disabled_at, NOW()) END WHERE id = $2",
+        )
+        .bind(disabled)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    if hash.is_some() || changes.disabled == Some(true) {
+        refresh::revoke_everything_in(&mut tx, id).await?;
+    }
+
+    let after = sqlx::query_as(&format!("{} WHERE id = $1", SELECT_USERS))
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok((before, after))
+}
+
+/// Sets a new password and signs the user out everywhere.
+pub async fn reset_password(
+    pool: &PgPool,
+    passwords: &PasswordHashing,
+    id: Uuid,
+    password: String,
+) -> Result<(), AccountError> {
+    let changes = AccountChanges { password: Some(password), ..Default::default() };
+    update(pool, passwords, id, changes).await?;
+    Ok(())
+}
+
+/// Disabling also ends every session and refresh token the user has.
+pub async fn set_disabled(pool: &PgPool, id: Uuid, disabled: bool) -> Result<(), AccountError> {
+    let mut tx = pool.begin().await?;
+    if disabled {
+        ensure_other_owner(&mut tx, id).await?;
+    }
+    let updated = sqlx::query(
+        "UPDATE users SET disabled_at = CASE WHEN $1 THEN COALESCE(disabled_at, NOW()) END WHERE id = $2",
+    )
+    .bind(disabled)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if updated == 0 {
+        return Err(AccountError::NotFound);
+    }
+    if disabled {
+        refresh::revoke_everything_in(&mut tx, id).await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Deletes the user, returning their username.
+pub async fn delete(pool: &PgPool, id: Uuid) -> Result<String, AccountError> {
+    let mut tx = pool.begin().await?;
+    ensure_other_owner(&mut tx, id).await?;
+    let username = sqlx::query_scalar("DELETE FROM users WHERE id = $1 RETURNING username")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AccountError::NotFound)?;
+    tx.commit().await?;
+    Ok(username)
+}
+
+/// Refuses to demote, disable or delete `id` if it is the last active owner,
+/// so the site can't be locked out of user management. Owner rows stay
+/// locked until the transaction ends.
+async fn ensure_other_owner(tx: &mut sqlx::PgConnection, id: Uuid) -> Result<(), AccountError> {
+    let owners: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM users WHERE role = 'owner' AND disabled_at IS NULL FOR UPDATE",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if owners.contains(&id) && owners.len() == 1 {
+        return Err(AccountError::Conflict(
+            "The last owner can't be removed, demoted or disabled".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn hash_password(passwords: &PasswordHashing, password: String) -> Result<String, AccountError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(AccountError::Invalid(format!(
+            "Passwords must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+
+    let passwords = passwords.clone();
+    tokio::task::spawn_blocking(move || passwords.hash(&password))
+        .await
+        .map_err(|e| AccountError::Hashing(e.to_string()))?
+        .map_err(|e| AccountError::Hashing(e.to_string()))
+}
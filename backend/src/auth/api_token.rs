@@ -0,0 +1,142 @@
+use crate::auth::{token, AdminUser};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use shared::{Permission, Role};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+
+/// Marks a bearer value as an API token rather than a JWT.
+pub const PREFIX: &str = "pat_";
+
+/// Longest lifetime a token can be created with; tokens may also never expire.
+pub const MAX_EXPIRY_DAYS: i64 = 365;
+
+/// A personal API token as listed to its owner. The secret itself is only
+/// returned by [`create`].
+#[derive(Serialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+pub fn is_api_token(bearer: &str) -> bool {
+    bearer.starts_with(PREFIX)
+}
+
+/// Turns requested scope names into permissions, refusing unknown scopes and
+/// ones the owner's role can't use.
+pub fn parse_scopes(role: Role, names: &[String]) -> Result<Vec<Permission>, String> {
+    let mut scopes = Vec::new();
+    for name in names {
+        let permission =
+            Permission::from_scope(name).ok_or_else(|| format!("Unknown scope: {}", name))?;
+        if !role.allows(permission) {
+            return Err(format!("The {} role can't grant {}", role.as_str(), name));
+        }
+        if !scopes.contains(&permission) {
+            scopes.push(permission);
+        }
+    }
+    Ok(scopes)
+}
+
+/// The expiry for a token valid for `days`, or none for a token that never expires.
+pub fn expiry(days: Option<i64>) -> Result<Option<DateTime<Utc>>, String> {
+    match days {
+        None => Ok(None),
+        Some(days @ 1..=MAX_EXPIRY_DAYS) => Ok(Some(Utc::now() + Duration::days(days))),
+        Some(_) => Err(format!("Expiry must be between 1 and {} days", MAX_EXPIRY_DAYS)),
+    }
+}
+
+/// Creates a token and returns it with its raw value, which is not stored.
+pub async fn create(
+    pool: &PgPool,
+    user_id: Uuid,
+    name: &str,
+    scopes: &[Permission],
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<(ApiToken, String), sqlx::Error> {
+    let value = format!("{}{}", PREFIX, token::generate());
+    let scopes: Vec<&str> = scopes.iter().map(|p| p.scope()).collect();
+
+    let created = sqlx::query_as(
+        r#"
+        INSERT INTO api_tokens (user_id, name, token_hash, scopes, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, name, scopes, created_at, last_used_at, expires_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(token::hash(&value))
+    .bind(scopes)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await?;
+    Ok((created, value))
+}
+
+/// The user's tokens that haven't been revoked, newest first. Expired tokens
+/// stay listed so their owner can see why a script stopped working.
+pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<Vec<ApiToken>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT id, name, scopes, created_at, last_used_at, expires_at
+        FROM api_tokens
+        WHERE user_id = $1 AND revoked_at IS NULL
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Revokes one of the user's tokens; false if there was no such live token.
+pub async fn revoke(pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE api_tokens SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+    )
+    .bind(id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Resolves a live token to its owner, limited to the token's scopes, and
+/// records the use.
+pub async fn lookup(pool: &PgPool, value: &str) -> Result<Option<AdminUser>, sqlx::Error> {
+    let row: Option<(Uuid, String, String, Vec<String>)> = sqlx::query_as(
+        r#"
+        UPDATE api_tokens t SET last_used_at = NOW()
+        FROM users u
+        WHERE t.token_hash = $1
+          AND t.user_id = u.id
+          AND t.revoked_at IS NULL
+          AND (t.expires_at IS NULL OR t.expires_at > NOW())
+          AND u.disabled_at IS NULL
+        RETURNING u.id, u.username, u.role, t.scopes
+        "#,
+    )
+    .bind(token::hash(value))
+    .fetch_optional(pool)
+    .await?;
+
+    let Some((id, username, role, scopes)) = row else {
+        return Ok(None);
+    };
+    let role = role.parse().map_err(|e: String| sqlx::Error::Decode(e.into()))?;
+
+    Ok(Some(AdminUser {
+        id,
+        username,
+        role,
+        scopes: Some(scopes.iter().filter_map(|s| Permission::from_scope(s)).collect()),
+    }))
+}
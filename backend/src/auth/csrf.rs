@@ -0,0 +1,169 @@
+use crate::auth::session::SessionConfig;
+use crate::auth::token;
+use crate::error::ApiError;
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, Method, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use shared::{CsrfToken, CSRF_COOKIE, CSRF_FIELD, CSRF_HEADER};
+
+/// Largest form body searched for the token field. Bigger uploads must send
+/// the token in the `X-CSRF-Token` header instead.
+const MAX_SCANNED_BODY: usize = 64 * 1024;
+
+/// Double-submit CSRF protection for every route.
+///
+/// Each browser gets a random token in a cookie, and the token is put in the
+/// request extensions so server-rendered forms can embed it (see the
+/// frontend's `CsrfField`). State-changing requests are refused when their
+/// `Origin` (or `Referer`) names another host, and requests a cross-site page
+/// could forge with the browser's cookies — form posts and bodyless requests
+/// without an `Authorization` header — must echo the cookie's token in the
+/// `csrf_token` field or the `X-CSRF-Token` header. JSON bodies need a CORS
+/// preflight, which we never grant, so they only get the origin check.
+pub async fn protect(
+    State(sessions): State<SessionConfig>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let cookie = CookieJar::from_headers(req.headers())
+        .get(CSRF_COOKIE)
+        .map(|c| c.value().to_string())
+        .filter(|v| !v.is_empty());
+
+    let mut req = if is_safe(req.method()) {
+        req
+    } else {
+        match verify(req, cookie.as_deref()).await {
+            Ok(req) => req,
+            Err(reason) => {
+                tracing::warn!("Rejected possible cross-site request: {}", reason);
+                return ApiError::forbidden("Cross-site request rejected")
+                    .with_code("csrf_rejected")
+                    .into_response();
+            }
+        }
+    };
+
+    let token = cookie.clone().unwrap_or_else(token::generate);
+    req.extensions_mut().insert(CsrfToken(token.clone()));
+
+    let mut res = next.run(req).await;
+    let is_page = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if cookie.is_none() && is_page {
+        // Not HttpOnly: client-side code reads it to fill the header and
+        // fields of forms rendered in the browser.
+        let cookie = Cookie::build((CSRF_COOKIE, token))
+            .path("/")
+            .secure(sessions.secure)
+            .same_site(SameSite::Strict)
+            .build();
+        if let Ok(value) = cookie.to_string().parse() {
+            res.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    res
+}
+
+fn is_safe(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE)
+}
+
+async fn verify(req: Request, cookie: Option<&str>) -> Result<Request, &'static str> {
+    check_origin(req.headers())?;
+
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let media_type = content_type.to_ascii_lowercase();
+    // Browsers never attach `Authorization` on their own, and anything but the
+    // CORS-safelisted content types triggers a preflight.
+    let forgeable = !req.headers().contains_key(header::AUTHORIZATION)
+        && (media_type.is_empty()
+            || media_type.starts_with("application/x-www-form-urlencoded")
+            || media_type.starts_with("multipart/form-data")
+            || media_type.starts_with("text/plain"));
+    if !forgeable {
+        return Ok(req);
+    }
+
+    let expected = cookie.ok_or("no CSRF cookie")?;
+    let matches = |sent: &str| token::constant_time_eq(sent.as_bytes(), expected.as_bytes());
+
+    if let Some(sent) = req.headers().get(CSRF_HEADER) {
+        return match sent.to_str() {
+            Ok(sent) if matches(sent) => Ok(req),
+            _ => Err("wrong CSRF header"),
+        };
+    }
+
+    let (parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_SCANNED_BODY)
+        .await
+        .map_err(|_| "form too large to check for a CSRF token")?;
+    match form_token(&content_type, bytes.clone()).await {
+        Some(sent) if matches(&sent) => Ok(Request::from_parts(parts, Body::from(bytes))),
+        Some(_) => Err("wrong CSRF token"),
+        None => Err("missing CSRF token"),
+    }
+}
+
+/// Requires `Origin`, or failing that `Referer`, to name the host the request
+/// was sent to. Clients that send neither (curl, scripts) pass.
+fn check_origin(headers: &HeaderMap) -> Result<(), &'static str> {
+    let Some(source) = headers
+        .get(header::ORIGIN)
+        .or_else(|| headers.get(header::REFERER))
+    else {
+        return Ok(());
+    };
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .ok_or("no Host header")?;
+
+    let source_host = source
+        .to_str()
+        .ok()
+        .and_then(|s| s.parse::<Uri>().ok())
+        .and_then(|uri| uri.authority().map(|a| a.as_str().to_string()))
+        .ok_or("unreadable Origin")?;
+
+    if source_host.eq_ignore_ascii_case(host) {
+        Ok(())
+    } else {
+        Err("Origin doesn't match Host")
+    }
+}
+
+/// The `csrf_token` field of a urlencoded or multipart form body.
+async fn form_token(content_type: &str, body: Bytes) -> Option<String> {
+    let media_type = content_type.to_ascii_lowercase();
+    if media_type.starts_with("application/x-www-form-urlencoded") {
+        return serde_urlencoded::from_bytes::<Vec<(String, String)>>(&body)
+            .ok()?
+            .into_iter()
+            .find(|(name, _)| name == CSRF_FIELD)
+            .map(|(_, value)| value);
+    }
+    if media_type.starts_with("multipart/form-data") {
+        let boundary = multer::parse_boundary(content_type).ok()?;
+        let stream = futures_util::stream::once(async move { Ok::<_, std::io::Error>(body) });
+        let mut multipart = multer::Multipart::new(stream, boundary);
+        while let Ok(Some(field)) = multipart.next_field().await {
+            if field.name() == Some(CSRF_FIELD) {
+                return field.text().await.ok();
+            }
+        }
+    }
+    None
+}
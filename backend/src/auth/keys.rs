@@ -0,0 +1,174 @@
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+// Only ever used in debug builds; release builds refuse to start with it.
+const DEV_SECRET: &str = "change-this-secret-key-in-production-environment";
+
+const DEFAULT_ISSUER: &str = "jakewray.ca";
+
+struct SigningKey {
+    kid: String,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl SigningKey {
+    fn from_secret(secret: &[u8]) -> Self {
+        Self {
+            kid: key_id(secret),
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+        }
+    }
+}
+
+struct RetiredKey {
+    key: SigningKey,
+    accept_until: Option<DateTime<Utc>>,
+}
+
+/// HMAC keys used to sign admin JWTs.
+///
+/// Tokens are always signed with the current key and carry its `kid`. During a
+/// rotation the previous key stays valid for verification so that outstanding
+/// tokens keep working until they expire or the rotation window closes.
+#[derive(Clone)]
+pub struct Keyring {
+    current: Arc<SigningKey>,
+    previous: Option<Arc<RetiredKey>>,
+    issuer: Arc<str>,
+}
+
+impl Keyring {
+    /// Loads keys from `JWT_SECRET` and, during a rotation, `JWT_PREVIOUS_SECRET`
+    /// (optionally bounded by `JWT_PREVIOUS_SECRET_EXPIRES_AT`, RFC 3339). The
+    /// `iss` claim comes from `JWT_ISSUER`.
+    pub fn from_env() -> Result<Self, String> {
+        let secret = match std::env::var("JWT_SECRET") {
+            Ok(s) if !s.trim().is_empty() => s,
+            _ if cfg!(debug_assertions) => {
+                tracing::warn!("JWT_SECRET is not set; using the development secret");
+                DEV_SECRET.to_string()
+            }
+            _ => return Err("JWT_SECRET environment variable must be set".into()),
+        };
+
+        if !cfg!(debug_assertions) && (secret == DEV_SECRET || secret.len() < 32) {
+            return Err(
+                "JWT_SECRET must be changed from the default and be at least 32 bytes".into(),
+            );
+        }
+
+        let previous = match std::env::var("JWT_PREVIOUS_SECRET") {
+            Ok(s) if !s.trim().is_empty() => {
+                let accept_until = match std::env::var("JWT_PREVIOUS_SECRET_EXPIRES_AT") {
+                    Ok(v) => Some(
+                        DateTime::parse_from_rfc3339(&v)
+                            .map_err(|e| format!("Invalid JWT_PREVIOUS_SECRET_EXPIRES_AT: {}", e))?
+                            .with_timezone(&Utc),
+                    ),
+                    Err(_) => None,
+                };
+                Some(RetiredKey {
+                    key: SigningKey::from_secret(s.as_bytes()),
+                    accept_until,
+                })
+            }
+            _ => None,
+        };
+
+        let keyring = Self {
+            current: Arc::new(SigningKey::from_secret(secret.as_bytes())),
+            previous: previous.map(Arc::new),
+            issuer: Arc::from(
+                std::env::var("JWT_ISSUER").unwrap_or_else(|_| DEFAULT_ISSUER.to_string()),
+            ),
+        };
+
+        if let Some(prev) = &keyring.previous {
+            if prev.key.kid == keyring.current.kid {
+                return Err("JWT_PREVIOUS_SECRET must differ from JWT_SECRET".into());
+            }
+            tracing::info!(
+                "JWT key rotation in progress: signing with {}, still accepting {}",
+                keyring.current.kid,
+                prev.key.kid
+            );
+        }
+
+        Ok(keyring)
+    }
+
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    /// Validation rules for tokens we issued: HS256, unexpired, and from our issuer.
+    pub fn validation(&self) -> Validation {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[self.issuer.as_ref()]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+        validation
+    }
+
+    /// Signs `claims` with the current key, setting the `kid` header.
+    pub fn encode<T: Serialize>(&self, claims: &T) -> jsonwebtoken::errors::Result<String> {
+        let header = Header {
+            kid: Some(self.current.kid.clone()),
+            ..Default::default()
+        };
+        jsonwebtoken::encode(&header, claims, &self.current.encoding)
+    }
+
+    /// Verifies `token` against the key named by its `kid` header. Tokens issued
+    /// before key ids were introduced have no `kid` and are tried against every
+    /// accepted key.
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        validation: &Validation,
+    ) -> jsonwebtoken::errors::Result<TokenData<T>> {
+        let header = jsonwebtoken::decode_header(token)?;
+        let candidates = self.accepted_keys();
+
+        match header.kid {
+            Some(kid) => {
+                let key = candidates
+                    .into_iter()
+                    .find(|k| k.kid == kid)
+                    .ok_or(jsonwebtoken::errors::ErrorKind::InvalidSignature)?;
+                jsonwebtoken::decode(token, &key.decoding, validation)
+            }
+            None => {
+                let mut last_err = jsonwebtoken::errors::ErrorKind::InvalidSignature.into();
+                for key in candidates {
+                    match jsonwebtoken::decode(token, &key.decoding, validation) {
+                        Ok(data) => return Ok(data),
+                        Err(e) => last_err = e,
+                    }
+                }
+                Err(last_err)
+            }
+        }
+    }
+
+    fn accepted_keys(&self) -> Vec<&SigningKey> {
+        let mut keys = vec![self.current.as_ref()];
+        if let Some(prev) = &self.previous {
+            if prev.accept_until.is_none_or(|until| Utc::now() < until) {
+                keys.push(&prev.key);
+            }
+        }
+        keys
+    }
+}
+
+/// Derives a stable, non-reversible key id from the secret so rotating only
+/// requires swapping environment variables.
+fn key_id(secret: &[u8]) -> String {
+    let digest = Sha256::digest(secret);
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,89 @@
+use chrono::{Duration, Utc};
+use keys::Keyring;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+
+pub mod accounts;
+pub mod api_token;
+pub mod csrf;
+pub mod keys;
+pub mod password;
+pub mod refresh;
+pub mod session;
+pub mod throttle;
+pub mod token;
+pub mod totp;
+pub mod user;
+pub mod webauthn;
+
+pub use user::AdminUser;
+
+/// Claims carried by admin access tokens.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub iss: String,
+    /// The user's `token_generation` at issue; "log out all devices" bumps it.
+    #[serde(default)]
+    pub generation: i32,
+}
+
+/// Claims of the short-lived token that carries a password-verified login
+/// through the TOTP step. The audience keeps it from being accepted as an
+/// access token.
+#[derive(Debug, Serialize, Deserialize)]
+struct MfaClaims {
+    sub: String,
+    exp: usize,
+    iat: usize,
+    iss: String,
+    aud: String,
+}
+
+const MFA_AUDIENCE: &str = "mfa";
+
+/// How long a user has to enter their TOTP code after their password.
+pub const MFA_CHALLENGE_TTL_MINUTES: i64 = 5;
+
+/// Access tokens are short-lived; clients renew them with a refresh token.
+pub const ACCESS_TOKEN_TTL_MINUTES: i64 = 15;
+
+/// `generation` is the user's current `token_generation`, from
+/// [`refresh::token_generation`].
+pub fn issue_access_token(
+    keys: &Keyring,
+    user_id: Uuid,
+    generation: i32,
+) -> jsonwebtoken::errors::Result<String> {
+    let now = Utc::now();
+    keys.encode(&Claims {
+        sub: user_id.to_string(),
+        exp: (now + Duration::minutes(ACCESS_TOKEN_TTL_MINUTES)).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        iss: keys.issuer().to_string(),
+        generation,
+    })
+}
+
+pub fn issue_mfa_challenge(keys: &Keyring, user_id: Uuid) -> jsonwebtoken::errors::Result<String> {
+    let now = Utc::now();
+    keys.encode(&MfaClaims {
+        sub: user_id.to_string(),
+        exp: (now + Duration::minutes(MFA_CHALLENGE_TTL_MINUTES)).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        iss: keys.issuer().to_string(),
+        aud: MFA_AUDIENCE.to_string(),
+    })
+}
+
+/// Returns the user a still-valid MFA challenge token was issued to.
+pub fn verify_mfa_challenge(keys: &Keyring, token: &str) -> Option<Uuid> {
+    let mut validation = keys.validation();
+    validation.set_audience(&[MFA_AUDIENCE]);
+    validation.set_required_spec_claims(&["exp", "iss", "sub", "aud"]);
+
+    let claims = keys.decode::<MfaClaims>(token, &validation).ok()?.claims;
+    Uuid::parse_str(&claims.sub).ok()
+}
@@ -0,0 +1,123 @@
+use argon2::password_hash::{
+    rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier as _, SaltString,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use crate::auth::token::constant_time_eq;
+use std::sync::Arc;
+
+/// Outcome of checking a password against the stored `users.password_hash`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    Invalid,
+    Valid,
+    /// The password matched, but the stored value is plaintext or was hashed
+    /// with different parameters and should be replaced.
+    NeedsRehash,
+}
+
+/// Argon2id hashing with cost parameters read from the environment.
+#[derive(Clone)]
+pub struct PasswordHashing {
+    params: Params,
+    // Verified against when the username doesn't exist so both paths cost the same.
+    dummy_hash: Arc<str>,
+}
+
+impl PasswordHashing {
+    pub fn new(params: Params) -> Result<Self, argon2::password_hash::Error> {
+        let mut hashing = Self {
+            params,
+            dummy_hash: Arc::from(""),
+        };
+        let dummy = SaltString::generate(&mut OsRng);
+        hashing.dummy_hash = Arc::from(hashing.hash(dummy.as_str())?);
+        Ok(hashing)
+    }
+
+    /// Reads `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS` and `ARGON2_PARALLELISM`,
+    /// falling back to the OWASP recommended minimums.
+    pub fn from_env() -> Result<Self, String> {
+        let read = |name: &str, default: u32| -> Result<u32, String> {
+            match std::env::var(name) {
+                Ok(v) => v.parse().map_err(|e| format!("Invalid {}: {}", name, e)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let params = Params::new(
+            read("ARGON2_MEMORY_KIB", 19 * 1024)?,
+            read("ARGON2_ITERATIONS", 2)?,
+            read("ARGON2_PARALLELISM", 1)?,
+            None,
+        )
+        .map_err(|e| format!("Invalid Argon2 parameters: {}", e))?;
+
+        Self::new(params).map_err(|e| format!("Failed to initialise password hashing: {}", e))
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+
+    /// Hashes `password` with a fresh random salt into a PHC string.
+    pub fn hash(&self, password: &str) -> Result<String, argon2::password_hash::Error> {
+        let salt = SaltString::generate(&mut OsRng);
+        Ok(self
+            .argon2()
+            .hash_password(password.as_bytes(), &salt)?
+            .to_string())
+    }
+
+    /// Checks `password` against a stored value. Values that aren't PHC strings
+    /// are legacy plaintext rows from before hashing was introduced; they are
+    /// still accepted once so the caller can upgrade them.
+    pub fn verify(&self, stored: &str, password: &str) -> Verification {
+        let parsed = match PasswordHash::new(stored) {
+            Ok(parsed) => parsed,
+            Err(_) => {
+                return if constant_time_eq(stored.as_bytes(), password.as_bytes()) {
+                    Verification::NeedsRehash
+                } else {
+                    Verification::Invalid
+                };
+            }
+        };
+
+        if self
+            .argon2()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_err()
+        {
+            return Verification::Invalid;
+        }
+
+        if self.is_current(&parsed) {
+            Verification::Valid
+        } else {
+            Verification::NeedsRehash
+        }
+    }
+
+    /// Burns the same amount of work as a real verification. Used when the
+    /// username is unknown so response timing doesn't reveal which accounts exist.
+    pub fn verify_dummy(&self, password: &str) {
+        let _ = self.verify(&self.dummy_hash, password);
+    }
+
+    fn is_current(&self, parsed: &PasswordHash<'_>) -> bool {
+        if parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+        {
+            return false;
+        }
+
+        match Params::try_from(parsed) {
+            Ok(p) => {
+                p.m_cost() == self.params.m_cost()
+                    && p.t_cost() == self.params.t_cost()
+                    && p.p_cost() == self.params.p_cost()
+            }
+            Err(_) => false,
+        }
+    }
+}
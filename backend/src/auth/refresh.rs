@@ -0,0 +1,165 @@
+use crate::auth::token;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+
+/// How long a refresh token stays usable if it is never rotated.
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+/// Result of presenting a refresh token.
+pub enum Rotation {
+    /// The token was live; it is now spent and replaced by `refresh_token`.
+    Rotated { user_id: Uuid, refresh_token: String },
+    /// The token was already used or revoked. Its whole family has been revoked
+    /// because a copy has probably leaked.
+    Reused { user_id: Uuid },
+    /// Unknown or expired.
+    Invalid,
+}
+
+#[derive(sqlx::FromRow)]
+struct RefreshRow {
+    id: Uuid,
+    user_id: Uuid,
+    family_id: Uuid,
+    expires_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Issues a refresh token starting a new family, returning the raw value.
+pub async fn issue(pool: &PgPool, user_id: Uuid) -> Result<String, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let value = insert(&mut tx, user_id, Uuid::new_v4()).await?;
+    tx.commit().await?;
+    Ok(value)
+}
+
+async fn insert(
+    tx: &mut sqlx::PgConnection,
+    user_id: Uuid,
+    family_id: Uuid,
+) -> Result<String, sqlx::Error> {
+    let value = token::generate();
+    sqlx::query(
+        "INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(user_id)
+    .bind(family_id)
+    .bind(token::hash(&value))
+    .bind(Utc::now() + Duration::days(REFRESH_TOKEN_TTL_DAYS))
+    .execute(tx)
+    .await?;
+    Ok(value)
+}
+
+/// Spends `value` and issues its successor in the same family.
+pub async fn rotate(pool: &PgPool, value: &str) -> Result<Rotation, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let row: Option<RefreshRow> = sqlx::query_as(
+        "SELECT id, user_id, family_id, expires_at, used_at, revoked_at FROM refresh_tokens WHERE token_hash = $1 FOR UPDATE",
+    )
+    .bind(token::hash(value))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(Rotation::Invalid);
+    };
+
+    if row.used_at.is_some() || row.revoked_at.is_some() {
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(row.family_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        tracing::warn!(
+            "Refresh token reuse detected for user {}; revoked family {}",
+            row.user_id,
+            row.family_id
+        );
+        return Ok(Rotation::Reused {
+            user_id: row.user_id,
+        });
+    }
+
+    if row.expires_at <= Utc::now() {
+        return Ok(Rotation::Invalid);
+    }
+
+    sqlx::query("UPDATE refresh_tokens SET used_at = NOW() WHERE id = $1")
+        .bind(row.id)
+        .execute(&mut *tx)
+        .await?;
+    let refresh_token = insert(&mut tx, row.user_id, row.family_id).await?;
+    tx.commit().await?;
+
+    Ok(Rotation::Rotated {
+        user_id: row.user_id,
+        refresh_token,
+    })
+}
+
+/// Revokes the family `value` belongs to, e.g. on logout.
+pub async fn revoke(pool: &PgPool, value: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE refresh_tokens SET revoked_at = NOW()
+        WHERE revoked_at IS NULL
+          AND family_id = (SELECT family_id FROM refresh_tokens WHERE token_hash = $1)
+        "#,
+    )
+    .bind(token::hash(value))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Revokes every refresh token, session, API token and outstanding access
+/// token for a user.
+pub async fn revoke_everything(pool: &PgPool, user_id: Uuid) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    revoke_everything_in(&mut tx, user_id).await?;
+    tx.commit().await
+}
+
+/// [`revoke_everything`] as part of a caller's transaction.
+pub async fn revoke_everything_in(
+    tx: &mut sqlx::PgConnection,
+    user_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE admin_sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE api_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("UPDATE users SET token_generation = token_generation + 1 WHERE id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    Ok(())
+}
+
+/// The generation new access tokens for `user_id` must carry.
+pub async fn token_generation(pool: &PgPool, user_id: Uuid) -> Result<i32, sqlx::Error> {
+    sqlx::query_scalar("SELECT token_generation FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
@@ -0,0 +1,188 @@
+use crate::auth::{token, AdminUser};
+use crate::state::AppState;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::{header, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use chrono::{Duration, Utc};
+use shared::Permission;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+
+pub const COOKIE_NAME: &str = "admin_session";
+
+/// Holds the MFA challenge between the password and TOTP steps of a form login.
+pub const MFA_COOKIE_NAME: &str = "admin_mfa";
+
+/// Settings for cookie-backed admin sessions.
+#[derive(Clone)]
+pub struct SessionConfig {
+    pub ttl: Duration,
+    pub secure: bool,
+}
+
+impl SessionConfig {
+    /// Reads `SESSION_TTL_HOURS` (default 24) and `SESSION_COOKIE_SECURE`
+    /// (default on in release builds, off in debug so plain-HTTP dev works).
+    pub fn from_env() -> Result<Self, String> {
+        let ttl_hours: i64 = match std::env::var("SESSION_TTL_HOURS") {
+            Ok(v) => v
+                .parse()
+                .map_err(|e| format!("Invalid SESSION_TTL_HOURS: {}", e))?,
+            Err(_) => 24,
+        };
+        let secure = match std::env::var("SESSION_COOKIE_SECURE") {
+            Ok(v) => v == "true" || v == "1",
+            Err(_) => !cfg!(debug_assertions),
+        };
+
+        Ok(Self {
+            ttl: Duration::hours(ttl_hours),
+            secure,
+        })
+    }
+
+    fn build_cookie(&self, name: &'static str, value: String, max_age: Duration) -> Cookie<'static> {
+        Cookie::build((name, value))
+            .path("/")
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(SameSite::Lax)
+            .max_age(time::Duration::seconds(max_age.num_seconds()))
+            .build()
+    }
+
+    pub fn cookie(&self, value: String) -> Cookie<'static> {
+        self.build_cookie(COOKIE_NAME, value, self.ttl)
+    }
+
+    pub fn removal_cookie(&self) -> Cookie<'static> {
+        let mut cookie = self.cookie(String::new());
+        cookie.make_removal();
+        cookie
+    }
+
+    pub fn mfa_cookie(&self, challenge: String) -> Cookie<'static> {
+        self.build_cookie(
+            MFA_COOKIE_NAME,
+            challenge,
+            Duration::minutes(crate::auth::MFA_CHALLENGE_TTL_MINUTES),
+        )
+    }
+
+    pub fn mfa_removal_cookie(&self) -> Cookie<'static> {
+        let mut cookie = self.mfa_cookie(String::new());
+        cookie.make_removal();
+        cookie
+    }
+}
+
+/// Starts a session for `user_id` and returns the raw cookie value.
+pub async fn create(
+    pool: &PgPool,
+    config: &SessionConfig,
+    user_id: Uuid,
+    user_agent: Option<&str>,
+) -> Result<String, sqlx::Error> {
+    let value = token::generate();
+    sqlx::query(
+        "INSERT INTO admin_sessions (user_id, token_hash, user_agent, expires_at) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(user_id)
+    .bind(token::hash(&value))
+    .bind(user_agent)
+    .bind(Utc::now() + config.ttl)
+    .execute(pool)
+    .await?;
+    Ok(value)
+}
+
+/// Resolves a live session cookie to its user, refreshing `last_seen_at`.
+pub async fn lookup(pool: &PgPool, value: &str) -> Result<Option<AdminUser>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        UPDATE admin_sessions s SET last_seen_at = NOW()
+        FROM users u
+        WHERE s.token_hash = $1
+          AND s.user_id = u.id
+          AND s.revoked_at IS NULL
+          AND s.expires_at > NOW()
+          AND u.disabled_at IS NULL
+        RETURNING u.id, u.username, u.role
+        "#,
+    )
+    .bind(token::hash(value))
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn revoke(pool: &PgPool, value: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE admin_sessions SET revoked_at = NOW() WHERE token_hash = $1 AND revoked_at IS NULL",
+    )
+    .bind(token::hash(value))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Redirects browser navigations to `/admin/*` pages to the login page when
+/// there is no valid session, so the check happens during SSR rather than in a
+/// client-side effect after hydration.
+pub async fn guard_admin_pages(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let path = req.uri().path();
+    let is_page_request = (req.method() == Method::GET || req.method() == Method::HEAD)
+        && req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("text/html"));
+
+    if !is_page_request
+        || !(path == "/admin" || path.starts_with("/admin/"))
+        || path == "/admin/login"
+    {
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+    match AdminUser::from_request_parts(&mut parts, &state).await {
+        Ok(user) => {
+            // Pages for things the role can't do bounce back to the dashboard.
+            if page_permission(parts.uri.path()).is_some_and(|p| !user.role.allows(p)) {
+                return Redirect::to("/admin/dashboard").into_response();
+            }
+            next.run(Request::from_parts(parts, body)).await
+        }
+        Err(_) => {
+            let target = parts
+                .uri
+                .path_and_query()
+                .map(|pq| pq.as_str())
+                .unwrap_or("/admin/dashboard");
+            let query = serde_urlencoded::to_string([("next", target)]).unwrap_or_default();
+            Redirect::to(&format!("/admin/login?{}", query)).into_response()
+        }
+    }
+}
+
+fn page_permission(path: &str) -> Option<Permission> {
+    let page = path.trim_end_matches('/');
+    match page {
+        "/admin/compose" => Some(Permission::DraftPosts),
+        "/admin/media" => Some(Permission::UploadMedia),
+        "/admin/sync" => Some(Permission::RunSync),
+        "/admin/users" => Some(Permission::ManageUsers),
+        "/admin/audit" => Some(Permission::ViewAuditLog),
+        _ => None,
+    }
+}
+
+/// Only same-site admin paths are accepted as post-login destinations.
+pub fn safe_next(next: Option<&str>) -> &str {
+    match next {
+        Some(n) if n.starts_with("/admin/") && !n.starts_with("/admin/login") => n,
+        _ => "/admin/dashboard",
+    }
+}
@@ -0,0 +1,233 @@
+use crate::error::ApiError;
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use chrono::Duration;
+use sqlx::PgPool;
+use std::net::{IpAddr, SocketAddr};
+
+/// Failed-login throttling, tracked per client IP and per username.
+///
+/// The first `free_attempts` failures are free. After that each further
+/// failure locks the key for `base_delay`, doubling every time up to
+/// `max_delay`. A quiet period of `max_delay` forgets old failures.
+#[derive(Clone)]
+pub struct LoginThrottle {
+    pub free_attempts: i32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+/// What a throttle row is keyed on.
+#[derive(Debug, Clone, Copy)]
+pub enum Scope {
+    Ip,
+    Username,
+}
+
+impl Scope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scope::Ip => "ip",
+            Scope::Username => "username",
+        }
+    }
+}
+
+impl LoginThrottle {
+    /// Reads `LOGIN_FREE_ATTEMPTS` (default 5), `LOGIN_LOCKOUT_BASE_SECONDS`
+    /// (default 30) and `LOGIN_LOCKOUT_MAX_SECONDS` (default 3600).
+    pub fn from_env() -> Result<Self, String> {
+        let read = |name: &str, default: i64| -> Result<i64, String> {
+            match std::env::var(name) {
+                Ok(v) => v.parse().map_err(|e| format!("Invalid {}: {}", name, e)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let free_attempts = read("LOGIN_FREE_ATTEMPTS", 5)?;
+        let free_attempts = i32::try_from(free_attempts)
+            .ok()
+            .filter(|n| *n >= 0)
+            .ok_or_else(|| format!("LOGIN_FREE_ATTEMPTS must be between 0 and {}", i32::MAX))?;
+        let seconds = |name: &str, default: i64| -> Result<Duration, String> {
+            Some(read(name, default)?)
+                .filter(|secs| *secs >= 1)
+                .and_then(Duration::try_seconds)
+                .ok_or_else(|| format!("{} must be a positive number of seconds", name))
+        };
+        let base_delay = seconds("LOGIN_LOCKOUT_BASE_SECONDS", 30)?;
+        let max_delay = seconds("LOGIN_LOCKOUT_MAX_SECONDS", 3600)?;
+        if base_delay > max_delay {
+            return Err("LOGIN_LOCKOUT_BASE_SECONDS must not exceed LOGIN_LOCKOUT_MAX_SECONDS".to_string());
+        }
+
+        Ok(Self {
+            free_attempts,
+            base_delay,
+            max_delay,
+        })
+    }
+
+    /// Lockout earned by the `failures`th consecutive failure.
+    fn lockout_for(&self, failures: i32) -> Option<Duration> {
+        let over = failures - self.free_attempts;
+        if over <= 0 {
+            return None;
+        }
+        // Cap the shift well before it overflows; max_delay applies anyway.
+        let factor = 1i32 << (over - 1).min(20);
+        let delay = self.base_delay.checked_mul(factor).unwrap_or(self.max_delay);
+        Some(delay.min(self.max_delay))
+    }
+
+    /// Returns how long the caller must still wait if any of `keys` is locked.
+    pub async fn check(
+        &self,
+        pool: &PgPool,
+        keys: &[(Scope, &str)],
+    ) -> Result<Option<Duration>, sqlx::Error> {
+        let mut longest: Option<Duration> = None;
+        for (scope, key) in keys {
+            let remaining: Option<f64> = sqlx::query_scalar(
+                r#"
+                SELECT EXTRACT(EPOCH FROM (locked_until - NOW()))::float8
+                FROM login_throttle
+                WHERE scope = $1 AND key = $2 AND locked_until > NOW()
+                "#,
+            )
+            .bind(scope.as_str())
+            .bind(*key)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+
+            if let Some(secs) = remaining {
+                let wait = Duration::seconds(secs.ceil() as i64);
+                longest = Some(longest.map_or(wait, |l| l.max(wait)));
+            }
+        }
+        Ok(longest)
+    }
+
+    /// Counts a failed attempt against every key, returning the longest
+    /// lockout it triggered.
+    pub async fn record_failure(
+        &self,
+        pool: &PgPool,
+        keys: &[(Scope, &str)],
+    ) -> Result<Option<Duration>, sqlx::Error> {
+        let mut longest: Option<Duration> = None;
+        for (scope, key) in keys {
+            let failures: i32 = sqlx::query_scalar(
+                r#"
+                INSERT INTO login_throttle (scope, key, failures, last_failure_at)
+                VALUES ($1, $2, 1, NOW())
+                ON CONFLICT (scope, key) DO UPDATE SET
+                    failures = CASE
+                        WHEN login_throttle.last_failure_at < NOW() - make_interval(secs => $3)
+                        THEN 1
+                        ELSE login_throttle.failures + 1
+                    END,
+                    last_failure_at = NOW()
+                RETURNING failures
+                "#,
+            )
+            .bind(scope.as_str())
+            .bind(*key)
+            .bind(self.max_delay.num_seconds() as f64)
+            .fetch_one(pool)
+            .await?;
+
+            if let Some(lockout) = self.lockout_for(failures) {
+                sqlx::query(
+                    "UPDATE login_throttle SET locked_until = NOW() + make_interval(secs => $3) WHERE scope = $1 AND key = $2",
+                )
+                .bind(scope.as_str())
+                .bind(*key)
+                .bind(lockout.num_seconds() as f64)
+                .execute(pool)
+                .await?;
+                tracing::warn!(
+                    "Locked {} {} out of admin login for {}s after {} failures",
+                    scope.as_str(),
+                    key,
+                    lockout.num_seconds(),
+                    failures
+                );
+                longest = Some(longest.map_or(lockout, |l| l.max(lockout)));
+            }
+        }
+
+        // Opportunistically drop rows nobody has failed against for a day.
+        sqlx::query(
+            "DELETE FROM login_throttle WHERE last_failure_at < NOW() - INTERVAL '1 day' AND (locked_until IS NULL OR locked_until < NOW())",
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(longest)
+    }
+
+    /// Clears the failure count for a key after a successful login. IP rows
+    /// are left alone so one valid account can't reset a guessing client.
+    pub async fn record_success(&self, pool: &PgPool, username: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM login_throttle WHERE scope = $1 AND key = $2")
+            .bind(Scope::Username.as_str())
+            .bind(username)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
+
+/// The address of the client making the request.
+///
+/// Requests arriving from a loopback or private address are assumed to come
+/// through our nginx front, so the last `X-Forwarded-For` entry (the one nginx
+/// appended) is used instead. Earlier entries are client-supplied and ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .ok_or_else(|| ApiError::internal("Client address unavailable"))?;
+
+        if !is_internal(peer) {
+            return Ok(ClientIp(peer));
+        }
+
+        let forwarded = parts
+            .headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .last()
+            .and_then(|ip| ip.trim().parse().ok());
+
+        Ok(ClientIp(forwarded.unwrap_or(peer)))
+    }
+}
+
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                // fc00::/7 unique local addresses, as used by docker networks.
+                || (v6.segments()[0] & 0xfe00) == 0xfc00
+                || v6.to_ipv4_mapped().is_some_and(|v4| is_internal(IpAddr::V4(v4)))
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::auth::token;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::Serialize;
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const ISSUER: &str = "jakewray.ca";
+const STEP_SECONDS: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Returned once when enrollment starts, for the authenticator app.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct Enrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+    /// The otpauth URI as an inline SVG QR code.
+    pub qr_svg: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct TotpRow {
+    totp_secret: Option<String>,
+    totp_enabled_at: Option<chrono::DateTime<chrono::Utc>>,
+    totp_last_step: Option<i64>,
+}
+
+fn build(secret: Vec<u8>, username: &str) -> Result<TOTP, String> {
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        STEP_SECONDS,
+        secret,
+        Some(ISSUER.to_string()),
+        username.replace(':', ""),
+    )
+    .map_err(|e| format!("Invalid TOTP parameters: {}", e))
+}
+
+fn from_base32(secret: &str, username: &str) -> Result<TOTP, String> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|_| "Stored TOTP secret is not valid base32".to_string())?;
+    build(bytes, username)
+}
+
+/// Whether the user must pass a TOTP check after their password.
+pub async fn is_enabled(pool: &PgPool, user_id: Uuid) -> Result<bool, sqlx::Error> {
+    let enabled: Option<bool> =
+        sqlx::query_scalar("SELECT totp_enabled_at IS NOT NULL FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(enabled.unwrap_or(false))
+}
+
+/// Generates and stores a fresh, not yet active secret for the user.
+pub async fn begin_enrollment(
+    pool: &PgPool,
+    user_id: Uuid,
+    username: &str,
+) -> Result<Enrollment, String> {
+    let secret = Secret::generate_secret()
+        .to_bytes()
+        .map_err(|_| "Failed to generate TOTP secret".to_string())?;
+    let totp = build(secret, username)?;
+    let secret = totp.get_secret_base32();
+    let otpauth_uri = totp.get_url();
+
+    let qr_svg = QrCode::new(otpauth_uri.as_bytes())
+        .map_err(|e| format!("Failed to render QR code: {}", e))?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+
+    sqlx::query(
+        "UPDATE users SET totp_secret = $1, totp_last_step = NULL WHERE id = $2 AND totp_enabled_at IS NULL",
+    )
+    .bind(&secret)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to store TOTP secret: {}", e))?;
+
+    Ok(Enrollment {
+        secret,
+        otpauth_uri,
+        qr_svg,
+    })
+}
+
+/// Activates a pending enrollment if `code` is valid for it, returning the
+/// plaintext recovery codes. `None` means the code didn't match.
+pub async fn confirm_enrollment(
+    pool: &PgPool,
+    user_id: Uuid,
+    username: &str,
+    code: &str,
+) -> Result<Option<Vec<String>>, String> {
+    let db_err = |e: sqlx::Error| format!("Database error: {}", e);
+
+    let mut tx = pool.begin().await.map_err(db_err)?;
+    let row: TotpRow = sqlx::query_as(
+        "SELECT totp_secret, totp_enabled_at, totp_last_step FROM users WHERE id = $1 FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(db_err)?;
+
+    let Some(secret) = row.totp_secret.filter(|_| row.totp_enabled_at.is_none()) else {
+        return Ok(None);
+    };
+    let Some(step) = matching_step(&from_base32(&secret, username)?, code, row.totp_last_step)
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "UPDATE users SET totp_enabled_at = NOW(), totp_last_step = $1 WHERE id = $2",
+    )
+    .bind(step)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(db_err)?;
+
+    let codes = replace_recovery_codes(&mut tx, user_id).await.map_err(db_err)?;
+    tx.commit().await.map_err(db_err)?;
+    Ok(Some(codes))
+}
+
+/// Turns the second factor off and discards the secret and recovery codes.
+pub async fn disable(pool: &PgPool, user_id: Uuid) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE users SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL WHERE id = $1",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+/// Checks a login code: either the current TOTP (each time step is accepted
+/// only once) or an unused recovery code, which is then consumed.
+pub async fn verify(
+    pool: &PgPool,
+    user_id: Uuid,
+    username: &str,
+    code: &str,
+) -> Result<bool, String> {
+    let db_err = |e: sqlx::Error| format!("Database error: {}", e);
+    let code = code.trim();
+
+    let mut tx = pool.begin().await.map_err(db_err)?;
+    let row: TotpRow = sqlx::query_as(
+        "SELECT totp_secret, totp_enabled_at, totp_last_step FROM users WHERE id = $1 FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(db_err)?;
+
+    let (Some(secret), Some(_)) = (row.totp_secret, row.totp_enabled_at) else {
+        return Ok(false);
+    };
+
+    if code.len() == 6 && code.bytes().all(|b| b.is_ascii_digit()) {
+        let totp = from_base32(&secret, username)?;
+        let Some(step) = matching_step(&totp, code, row.totp_last_step) else {
+            return Ok(false);
+        };
+        sqlx::query("UPDATE users SET totp_last_step = $1 WHERE id = $2")
+            .bind(step)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_err)?;
+        tx.commit().await.map_err(db_err)?;
+        return Ok(true);
+    }
+
+    let used = sqlx::query(
+        "UPDATE totp_recovery_codes SET used_at = NOW() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(token::hash(&normalize_recovery_code(code)))
+    .execute(&mut *tx)
+    .await
+    .map_err(db_err)?
+    .rows_affected()
+        > 0;
+    tx.commit().await.map_err(db_err)?;
+
+    if used {
+        tracing::info!("User {} signed in with a recovery code", user_id);
+    }
+    Ok(used)
+}
+
+/// Finds the time step (current ±1) whose code equals `code`, skipping steps
+/// at or before `last_step` so a code can't be replayed.
+fn matching_step(totp: &TOTP, code: &str, last_step: Option<i64>) -> Option<i64> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let current = (now / STEP_SECONDS) as i64;
+
+    (current - 1..=current + 1)
+        .filter(|step| last_step.is_none_or(|last| *step > last))
+        .find(|step| {
+            let expected = totp.generate(*step as u64 * STEP_SECONDS);
+            token::constant_time_eq(expected.as_bytes(), code.as_bytes())
+        })
+}
+
+async fn replace_recovery_codes(
+    tx: &mut sqlx::PgConnection,
+    user_id: Uuid,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let code = new_recovery_code();
+        sqlx::query("INSERT INTO totp_recovery_codes (user_id, code_hash) VALUES ($1, $2)")
+            .bind(user_id)
+            .bind(token::hash(&normalize_recovery_code(&code)))
+            .execute(&mut *tx)
+            .await?;
+        codes.push(code);
+    }
+    Ok(codes)
+}
+
+/// Ten random lowercase alphanumerics (about 50 bits), shown as `xxxxx-xxxxx`.
+fn new_recovery_code() -> String {
+    let raw = token::generate();
+    let chars: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .take(10)
+        .collect();
+    format!("{}-{}", &chars[..5], &chars[5..])
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
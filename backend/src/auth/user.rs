@@ -0,0 +1,190 @@
+use crate::auth::keys::Keyring;
+use crate::auth::{api_token, session, Claims};
+use crate::error::ApiError;
+use axum::async_trait;
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::{header, request::Parts};
+use axum::response::{IntoResponse, Response};
+use axum_extra::extract::cookie::CookieJar;
+use serde::Serialize;
+use shared::{Permission, Role};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+
+/// An authenticated admin, resolved from a verified bearer token, an API
+/// token or a live session cookie.
+///
+/// Use it as a handler argument to require authentication. Routes registered
+/// in `api::admin::router` before its `route_layer` are also guarded by it, so
+/// handlers there only need to extract it when they care who the caller is.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct AdminUser {
+    pub id: Uuid,
+    pub username: String,
+    #[sqlx(try_from = "String")]
+    pub role: Role,
+    /// Set when the request used an API token: only these permissions are
+    /// usable, and only where the role also grants them.
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<Permission>>,
+}
+
+impl AdminUser {
+    /// Refuses with 403 unless the user's role grants `permission` and, for
+    /// API tokens, the token has the matching scope.
+    pub fn require(&self, permission: Permission) -> Result<(), AuthError> {
+        let in_scope = self.scopes.as_ref().is_none_or(|s| s.contains(&permission));
+        if self.role.allows(permission) && in_scope {
+            Ok(())
+        } else {
+            tracing::warn!(
+                "User {} ({}) was refused {:?}",
+                self.username,
+                self.role.as_str(),
+                permission
+            );
+            Err(AuthError::Forbidden)
+        }
+    }
+
+    /// Refuses API tokens. Changing how an account signs in, or minting more
+    /// tokens, needs a real login so a leaked token can't entrench itself.
+    pub fn require_interactive(&self) -> Result<(), AuthError> {
+        if self.scopes.is_some() {
+            tracing::warn!("User {} tried an account change with an API token", self.username);
+            return Err(AuthError::Forbidden);
+        }
+        Ok(())
+    }
+}
+
+/// Why a request was refused authentication.
+#[derive(Debug)]
+pub enum AuthError {
+    MissingToken,
+    InvalidToken,
+    UnknownUser,
+    /// Authenticated, but the role doesn't allow the action.
+    Forbidden,
+    Internal,
+}
+
+impl From<AuthError> for ApiError {
+    fn from(err: AuthError) -> Self {
+        match err {
+            AuthError::MissingToken => {
+                ApiError::unauthorized("Not signed in").with_code("not_signed_in")
+            }
+            AuthError::InvalidToken => {
+                ApiError::unauthorized("Invalid or expired token").with_code("invalid_token")
+            }
+            AuthError::UnknownUser => ApiError::unauthorized("Unknown user").with_code("unknown_user"),
+            AuthError::Forbidden => ApiError::forbidden("Not allowed"),
+            AuthError::Internal => ApiError::internal("Authentication failed"),
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AdminUser
+where
+    Keyring: FromRef<S>,
+    PgPool: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // The router-level layer and the handler both extract this; only hit
+        // the database once per request.
+        if let Some(user) = parts.extensions.get::<AdminUser>() {
+            return Ok(user.clone());
+        }
+
+        let pool = PgPool::from_ref(state);
+
+        let bearer = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.strip_prefix("Bearer "));
+
+        let user = match bearer {
+            Some(token) if api_token::is_api_token(token) => api_token::lookup(&pool, token)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to look up API token: {}", e);
+                    AuthError::Internal
+                })?
+                .ok_or(AuthError::InvalidToken)?,
+            Some(token) => user_from_bearer(&Keyring::from_ref(state), &pool, token).await?,
+            None => {
+                let jar = CookieJar::from_headers(&parts.headers);
+                let cookie = jar.get(session::COOKIE_NAME).ok_or(AuthError::MissingToken)?;
+                session::lookup(&pool, cookie.value())
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to look up admin session: {}", e);
+                        AuthError::Internal
+                    })?
+                    .ok_or(AuthError::InvalidToken)?
+            }
+        };
+
+        parts.extensions.insert(user.clone());
+        Ok(user)
+    }
+}
+
+async fn user_from_bearer(
+    keys: &Keyring,
+    pool: &PgPool,
+    token: &str,
+) -> Result<AdminUser, AuthError> {
+    let claims = keys
+        .decode::<Claims>(token, &keys.validation())
+        .map_err(|e| {
+            tracing::debug!("Rejected admin token: {}", e);
+            AuthError::InvalidToken
+        })?
+        .claims;
+
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AuthError::InvalidToken)?;
+
+    let row: Option<(Uuid, String, String, i32)> = sqlx::query_as(
+        "SELECT id, username, role, token_generation FROM users WHERE id = $1 AND disabled_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to load admin user {}: {}", user_id, e);
+        AuthError::Internal
+    })?;
+
+    let (id, username, role, generation) = row.ok_or(AuthError::UnknownUser)?;
+
+    // "Log out all devices" invalidates every access token issued before it.
+    if claims.generation != generation {
+        return Err(AuthError::InvalidToken);
+    }
+
+    let role = role.parse().map_err(|e| {
+        tracing::error!("User {} has an invalid role: {}", id, e);
+        AuthError::Internal
+    })?;
+
+    Ok(AdminUser {
+        id,
+        username,
+        role,
+        scopes: None,
+    })
+}
@@ -0,0 +1,740 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use chrono::{Duration, Utc};
+use ciborium::Value;
+use p256::ecdsa::signature::Verifier as _;
+use p256::ecdsa::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::types::Uuid;
+use sqlx::PgPool;
+
+const CHALLENGE_TTL_MINUTES: i64 = 5;
+/// COSE algorithm identifier for ECDSA with P-256 and SHA-256.
+const COSE_ES256: i64 = -7;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_DATA: u8 = 0x40;
+
+/// Relying party settings; the origin must match what the browser reports.
+///
+/// Only what our own browsers need is supported: ES256 keys, `"none"`
+/// attestation, and discoverable credentials with user verification, so a
+/// passkey alone is enough to sign in.
+#[derive(Clone)]
+pub struct WebauthnConfig {
+    pub rp_id: String,
+    pub rp_name: String,
+    pub origin: String,
+}
+
+impl WebauthnConfig {
+    /// Reads `WEBAUTHN_RP_ID` (default `localhost`), `WEBAUTHN_ORIGIN`
+    /// (default `http://localhost:3000`) and `WEBAUTHN_RP_NAME`.
+    pub fn from_env() -> Result<Self, String> {
+        let rp_id = std::env::var("WEBAUTHN_RP_ID").unwrap_or_else(|_| "localhost".to_string());
+        let origin = std::env::var("WEBAUTHN_ORIGIN")
+            .unwrap_or_else(|_| "http://localhost:3000".to_string());
+        let rp_name =
+            std::env::var("WEBAUTHN_RP_NAME").unwrap_or_else(|_| "jakewray.ca admin".to_string());
+
+        let host = origin
+            .split_once("://")
+            .map(|(_, rest)| rest.split([':', '/']).next().unwrap_or_default())
+            .ok_or_else(|| format!("Invalid WEBAUTHN_ORIGIN: {}", origin))?;
+        if host != rp_id && !host.ends_with(&format!(".{}", rp_id)) {
+            return Err(format!(
+                "WEBAUTHN_RP_ID {} is not a registrable suffix of WEBAUTHN_ORIGIN {}",
+                rp_id, origin
+            ));
+        }
+
+        Ok(Self {
+            rp_id,
+            rp_name,
+            origin,
+        })
+    }
+}
+
+/// Options for `navigator.credentials.create/get`, with binary fields as
+/// base64url. `challenge_id` is echoed back with the browser's response.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct Ceremony {
+    pub challenge_id: Uuid,
+    pub public_key: serde_json::Value,
+}
+
+/// The browser's answer to `navigator.credentials.create`.
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct RegistrationCredential {
+    pub id: String,
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+/// The browser's answer to `navigator.credentials.get`.
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct AssertionCredential {
+    pub id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+#[derive(Serialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct Passkey {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: chrono::DateTime<Utc>,
+    pub last_used_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// A credential accepted by `verify_registration`, ready to store.
+#[derive(Debug)]
+pub struct NewCredential {
+    pub credential_id: Vec<u8>,
+    /// SEC1 uncompressed P-256 point.
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    flags: u8,
+    sign_count: u32,
+    /// Credential ID and SEC1 public key, present during registration.
+    attested: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+fn decode(field: &str) -> Result<Vec<u8>, &'static str> {
+    URL_SAFE_NO_PAD
+        .decode(field.trim_end_matches('='))
+        .map_err(|_| "field is not base64url")
+}
+
+async fn store_challenge(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    purpose: &str,
+    challenge: &[u8],
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query("DELETE FROM webauthn_challenges WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+    sqlx::query_scalar(
+        "INSERT INTO webauthn_challenges (user_id, purpose, challenge, expires_at) VALUES ($1, $2, $3, $4) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(purpose)
+    .bind(challenge)
+    .bind(Utc::now() + Duration::minutes(CHALLENGE_TTL_MINUTES))
+    .fetch_one(pool)
+    .await
+}
+
+/// Removes and returns a live challenge, so each one is usable once.
+async fn take_challenge(
+    pool: &PgPool,
+    challenge_id: Uuid,
+    purpose: &str,
+) -> Result<Option<(Option<Uuid>, Vec<u8>)>, sqlx::Error> {
+    sqlx::query_as(
+        "DELETE FROM webauthn_challenges WHERE id = $1 AND purpose = $2 AND expires_at > NOW() RETURNING user_id, challenge",
+    )
+    .bind(challenge_id)
+    .bind(purpose)
+    .fetch_optional(pool)
+    .await
+}
+
+fn new_challenge() -> Vec<u8> {
+    use rand::RngCore as _;
+    let mut bytes = vec![0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Starts registering a new passkey for a signed-in user.
+pub async fn start_registration(
+    pool: &PgPool,
+    config: &WebauthnConfig,
+    user_id: Uuid,
+    username: &str,
+) -> Result<Ceremony, sqlx::Error> {
+    let existing: Vec<Vec<u8>> =
+        sqlx::query_scalar("SELECT credential_id FROM webauthn_credentials WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+    let challenge = new_challenge();
+    let challenge_id = store_challenge(pool, Some(user_id), "register", &challenge).await?;
+
+    Ok(Ceremony {
+        challenge_id,
+        public_key: json!({
+            "challenge": URL_SAFE_NO_PAD.encode(&challenge),
+            "rp": { "id": config.rp_id, "name": config.rp_name },
+            "user": {
+                "id": URL_SAFE_NO_PAD.encode(user_id.as_bytes()),
+                "name": username,
+                "displayName": username,
+            },
+            "pubKeyCredParams": [{ "type": "public-key", "alg": COSE_ES256 }],
+            "timeout": CHALLENGE_TTL_MINUTES * 60 * 1000,
+            "attestation": "none",
+            "authenticatorSelection": {
+                "residentKey": "required",
+                "userVerification": "required",
+            },
+            "excludeCredentials": existing
+                .iter()
+                .map(|id| json!({ "type": "public-key", "id": URL_SAFE_NO_PAD.encode(id) }))
+                .collect::<Vec<_>>(),
+        }),
+    })
+}
+
+/// Checks the browser's response and stores the new passkey. `None` means
+/// the response was rejected.
+pub async fn finish_registration(
+    pool: &PgPool,
+    config: &WebauthnConfig,
+    user_id: Uuid,
+    challenge_id: Uuid,
+    name: &str,
+    credential: &RegistrationCredential,
+) -> Result<Option<Uuid>, String> {
+    let db_err = |e: sqlx::Error| format!("Database error: {}", e);
+
+    let Some((Some(owner), challenge)) = take_challenge(pool, challenge_id, "register")
+        .await
+        .map_err(db_err)?
+    else {
+        return Ok(None);
+    };
+    if owner != user_id {
+        return Ok(None);
+    }
+
+    let new = match verify_registration(config, &challenge, credential) {
+        Ok(new) => new,
+        Err(reason) => {
+            tracing::warn!("Rejected passkey registration for {}: {}", user_id, reason);
+            return Ok(None);
+        }
+    };
+
+    let id = sqlx::query_scalar(
+        r#"
+        INSERT INTO webauthn_credentials (user_id, credential_id, public_key, sign_count, name)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (credential_id) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(user_id)
+    .bind(&new.credential_id)
+    .bind(&new.public_key)
+    .bind(i64::from(new.sign_count))
+    .bind(name)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_err)?;
+    Ok(id)
+}
+
+/// Starts a usernameless sign-in; the browser offers any passkey it holds
+/// for this site.
+pub async fn start_authentication(
+    pool: &PgPool,
+    config: &WebauthnConfig,
+) -> Result<Ceremony, sqlx::Error> {
+    let challenge = new_challenge();
+    let challenge_id = store_challenge(pool, None, "login", &challenge).await?;
+
+    Ok(Ceremony {
+        challenge_id,
+        public_key: json!({
+            "challenge": URL_SAFE_NO_PAD.encode(&challenge),
+            "rpId": config.rp_id,
+            "timeout": CHALLENGE_TTL_MINUTES * 60 * 1000,
+            "userVerification": "required",
+            "allowCredentials": [],
+        }),
+    })
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredCredential {
+    id: Uuid,
+    user_id: Uuid,
+    public_key: Vec<u8>,
+    sign_count: i64,
+}
+
+/// Verifies a sign-in assertion and returns the user it belongs to. `None`
+/// means the assertion was rejected.
+pub async fn finish_authentication(
+    pool: &PgPool,
+    config: &WebauthnConfig,
+    challenge_id: Uuid,
+    credential: &AssertionCredential,
+) -> Result<Option<Uuid>, String> {
+    let db_err = |e: sqlx::Error| format!("Database error: {}", e);
+
+    let Some((_, challenge)) = take_challenge(pool, challenge_id, "login")
+        .await
+        .map_err(db_err)?
+    else {
+        return Ok(None);
+    };
+    let Ok(credential_id) = decode(&credential.id) else {
+        return Ok(None);
+    };
+
+    let mut tx = pool.begin().await.map_err(db_err)?;
+    let stored: Option<StoredCredential> = sqlx::query_as(
+        "SELECT id, user_id, public_key, sign_count FROM webauthn_credentials WHERE credential_id = $1 FOR UPDATE",
+    )
+    .bind(&credential_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(db_err)?;
+    let Some(stored) = stored else {
+        return Ok(None);
+    };
+
+    let sign_count = match verify_assertion(
+        config,
+        &challenge,
+        stored.user_id,
+        &stored.public_key,
+        stored.sign_count,
+        credential,
+    ) {
+        Ok(count) => count,
+        Err(reason) => {
+            tracing::warn!("Rejected passkey sign-in for {}: {}", stored.user_id, reason);
+            return Ok(None);
+        }
+    };
+
+    sqlx::query("UPDATE webauthn_credentials SET sign_count = $1, last_used_at = NOW() WHERE id = $2")
+        .bind(i64::from(sign_count))
+        .bind(stored.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_err)?;
+    tx.commit().await.map_err(db_err)?;
+
+    Ok(Some(stored.user_id))
+}
+
+pub async fn list(pool: &PgPool, user_id: Uuid) -> Result<Vec<Passkey>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id, name, created_at, last_used_at FROM webauthn_credentials WHERE user_id = $1 ORDER BY created_at",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Deletes one of the user's passkeys, returning whether it existed.
+pub async fn remove(pool: &PgPool, user_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM webauthn_credentials WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Registration checks from WebAuthn §7.1 for `"none"` attestation.
+pub fn verify_registration(
+    config: &WebauthnConfig,
+    challenge: &[u8],
+    credential: &RegistrationCredential,
+) -> Result<NewCredential, &'static str> {
+    verify_client_data(
+        config,
+        "webauthn.create",
+        challenge,
+        &decode(&credential.client_data_json)?,
+    )?;
+
+    let attestation = decode(&credential.attestation_object)?;
+    let attestation: Value =
+        ciborium::from_reader(attestation.as_slice()).map_err(|_| "attestation is not CBOR")?;
+    let auth_data = attestation
+        .as_map()
+        .and_then(|map| {
+            map.iter()
+                .find(|(k, _)| k.as_text() == Some("authData"))
+                .and_then(|(_, v)| v.as_bytes())
+        })
+        .ok_or("attestation has no authData")?;
+
+    let auth_data = parse_authenticator_data(auth_data)?;
+    check_rp_and_flags(config, &auth_data)?;
+    let (credential_id, public_key) = auth_data.attested.ok_or("no attested credential")?;
+    if decode(&credential.id)? != credential_id {
+        return Err("credential id mismatch");
+    }
+
+    Ok(NewCredential {
+        credential_id,
+        public_key,
+        sign_count: auth_data.sign_count,
+    })
+}
+
+/// Assertion checks from WebAuthn §7.2. Returns the new signature counter.
+pub fn verify_assertion(
+    config: &WebauthnConfig,
+    challenge: &[u8],
+    user_id: Uuid,
+    public_key: &[u8],
+    stored_count: i64,
+    credential: &AssertionCredential,
+) -> Result<u32, &'static str> {
+    if let Some(handle) = &credential.user_handle {
+        if decode(handle)? != user_id.as_bytes() {
+            return Err("user handle mismatch");
+        }
+    }
+
+    let client_data = decode(&credential.client_data_json)?;
+    verify_client_data(config, "webauthn.get", challenge, &client_data)?;
+
+    let raw_auth_data = decode(&credential.authenticator_data)?;
+    let auth_data = parse_authenticator_data(&raw_auth_data)?;
+    check_rp_and_flags(config, &auth_data)?;
+
+    let key = VerifyingKey::from_sec1_bytes(public_key).map_err(|_| "stored key is invalid")?;
+    let signature =
+        Signature::from_der(&decode(&credential.signature)?).map_err(|_| "malformed signature")?;
+    let mut signed = raw_auth_data.clone();
+    signed.extend_from_slice(&Sha256::digest(&client_data));
+    key.verify(&signed, &signature)
+        .map_err(|_| "bad signature")?;
+
+    // Authenticators that keep a counter must always increase it; a repeat
+    // suggests a cloned key. Synced passkeys report zero throughout.
+    let count = auth_data.sign_count;
+    if (count != 0 || stored_count != 0) && i64::from(count) <= stored_count {
+        return Err("signature counter did not increase");
+    }
+    Ok(count)
+}
+
+fn verify_client_data(
+    config: &WebauthnConfig,
+    kind: &str,
+    challenge: &[u8],
+    raw: &[u8],
+) -> Result<(), &'static str> {
+    let client_data: ClientData =
+        serde_json::from_slice(raw).map_err(|_| "clientDataJSON is malformed")?;
+    if client_data.kind != kind {
+        return Err("wrong ceremony type");
+    }
+    if decode(&client_data.challenge)? != challenge {
+        return Err("challenge mismatch");
+    }
+    if client_data.origin != config.origin {
+        return Err("origin mismatch");
+    }
+    Ok(())
+}
+
+fn check_rp_and_flags(
+    config: &WebauthnConfig,
+    auth_data: &AuthenticatorData<'_>,
+) -> Result<(), &'static str> {
+    if auth_data.rp_id_hash != Sha256::digest(config.rp_id.as_bytes()).as_slice() {
+        return Err("RP ID hash mismatch");
+    }
+    if auth_data.flags & FLAG_USER_PRESENT == 0 {
+        return Err("user not present");
+    }
+    if auth_data.flags & FLAG_USER_VERIFIED == 0 {
+        return Err("user not verified");
+    }
+    Ok(())
+}
+
+fn parse_authenticator_data(data: &[u8]) -> Result<AuthenticatorData<'_>, &'static str> {
+    const TOO_SHORT: &str = "authenticator data is truncated";
+    if data.len() < 37 {
+        return Err(TOO_SHORT);
+    }
+    let flags = data[32];
+    let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+    let attested = if flags & FLAG_ATTESTED_DATA != 0 {
+        // aaguid (16 bytes), credential ID length (2 bytes), ID, COSE key.
+        let rest = data.get(37 + 16..).ok_or(TOO_SHORT)?;
+        let id_len = u16::from_be_bytes([*rest.first().ok_or(TOO_SHORT)?, *rest.get(1).ok_or(TOO_SHORT)?])
+            as usize;
+        let credential_id = rest.get(2..2 + id_len).ok_or(TOO_SHORT)?.to_vec();
+        let cose_key: Value = ciborium::from_reader(&rest[2 + id_len..])
+            .map_err(|_| "credential public key is not CBOR")?;
+        Some((credential_id, es256_public_key(&cose_key)?))
+    } else {
+        None
+    };
+
+    Ok(AuthenticatorData {
+        rp_id_hash: &data[..32],
+        flags,
+        sign_count,
+        attested,
+    })
+}
+
+/// Converts a COSE EC2 P-256 key into a SEC1 uncompressed point.
+fn es256_public_key(key: &Value) -> Result<Vec<u8>, &'static str> {
+    let map = key.as_map().ok_or("COSE key is not a map")?;
+    let field = |label: i64| {
+        map.iter()
+            .find(|(k, _)| k.as_integer().is_some_and(|k| i128::from(k) == i128::from(label)))
+            .map(|(_, v)| v)
+    };
+    let int = |label: i64| field(label).and_then(Value::as_integer).map(i128::from);
+
+    // kty = EC2, alg = ES256, crv = P-256.
+    if int(1) != Some(2) || int(3) != Some(i128::from(COSE_ES256)) || int(-1) != Some(1) {
+        return Err("only ES256 passkeys are supported");
+    }
+    let x = field(-2).and_then(Value::as_bytes).ok_or("COSE key has no x")?;
+    let y = field(-3).and_then(Value::as_bytes).ok_or("COSE key has no y")?;
+
+    let mut point = Vec::with_capacity(65);
+    point.push(0x04);
+    point.extend_from_slice(x);
+    point.extend_from_slice(y);
+    VerifyingKey::from_sec1_bytes(&point).map_err(|_| "COSE key is not a valid P-256 point")?;
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer as _;
+    use p256::ecdsa::SigningKey;
+
+    const CHALLENGE: &[u8] = b"a challenge of thirty-two bytes!";
+    const CREDENTIAL_ID: &[u8] = b"software authenticator";
+    const USER_ID: Uuid = Uuid::from_u128(0x5eed);
+
+    fn config() -> WebauthnConfig {
+        WebauthnConfig {
+            rp_id: "jakewray.dev".to_string(),
+            rp_name: "jakewray.ca admin".to_string(),
+            origin: "https://jakewray.dev".to_string(),
+        }
+    }
+
+    /// What the software authenticator and browser put in a response; each
+    /// test changes one field from the valid default.
+    struct Response<'a> {
+        kind: &'a str,
+        challenge: &'a [u8],
+        origin: &'a str,
+        rp_id: &'a str,
+        flags: u8,
+        sign_count: u32,
+    }
+
+    impl Response<'_> {
+        fn create() -> Self {
+            Self {
+                kind: "webauthn.create",
+                challenge: CHALLENGE,
+                origin: "https://jakewray.dev",
+                rp_id: "jakewray.dev",
+                flags: FLAG_USER_PRESENT | FLAG_USER_VERIFIED,
+                sign_count: 0,
+            }
+        }
+
+        fn get() -> Self {
+            Self {
+                kind: "webauthn.get",
+                sign_count: 1,
+                ..Self::create()
+            }
+        }
+
+        fn client_data(&self) -> Vec<u8> {
+            json!({
+                "type": self.kind,
+                "challenge": URL_SAFE_NO_PAD.encode(self.challenge),
+                "origin": self.origin,
+            })
+            .to_string()
+            .into_bytes()
+        }
+
+        /// Authenticator data, carrying `key` as the attested credential
+        /// when registering.
+        fn authenticator_data(&self, key: Option<&SigningKey>) -> Vec<u8> {
+            let mut data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+            let flags = if key.is_some() { self.flags | FLAG_ATTESTED_DATA } else { self.flags };
+            data.push(flags);
+            data.extend_from_slice(&self.sign_count.to_be_bytes());
+            if let Some(key) = key {
+                let point = key.verifying_key().to_encoded_point(false);
+                let cose_key = Value::Map(vec![
+                    (Value::from(1), Value::from(2)),
+                    (Value::from(3), Value::from(COSE_ES256)),
+                    (Value::from(-1), Value::from(1)),
+                    (Value::from(-2), Value::Bytes(point.x().unwrap().to_vec())),
+                    (Value::from(-3), Value::Bytes(point.y().unwrap().to_vec())),
+                ]);
+                data.extend_from_slice(&[0; 16]);
+                data.extend_from_slice(&(CREDENTIAL_ID.len() as u16).to_be_bytes());
+                data.extend_from_slice(CREDENTIAL_ID);
+                ciborium::into_writer(&cose_key, &mut data).unwrap();
+            }
+            data
+        }
+    }
+
+    fn authenticator() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    fn register(key: &SigningKey, response: &Response<'_>) -> Result<NewCredential, &'static str> {
+        let attestation = Value::Map(vec![
+            (Value::from("fmt"), Value::from("none")),
+            (Value::from("attStmt"), Value::Map(Vec::new())),
+            (Value::from("authData"), Value::Bytes(response.authenticator_data(Some(key)))),
+        ]);
+        let mut attestation_object = Vec::new();
+        ciborium::into_writer(&attestation, &mut attestation_object).unwrap();
+
+        verify_registration(
+            &config(),
+            CHALLENGE,
+            &RegistrationCredential {
+                id: URL_SAFE_NO_PAD.encode(CREDENTIAL_ID),
+                client_data_json: URL_SAFE_NO_PAD.encode(response.client_data()),
+                attestation_object: URL_SAFE_NO_PAD.encode(attestation_object),
+            },
+        )
+    }
+
+    /// Signs in with a credential registered to [`authenticator`], signing
+    /// the assertion with `signer`.
+    fn sign_in(
+        signer: &SigningKey,
+        response: &Response<'_>,
+        stored_count: i64,
+    ) -> Result<u32, &'static str> {
+        let public_key = register(&authenticator(), &Response::create()).unwrap().public_key;
+        let client_data = response.client_data();
+        let authenticator_data = response.authenticator_data(None);
+        let mut signed = authenticator_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&client_data));
+        let signature: Signature = signer.sign(&signed);
+
+        verify_assertion(
+            &config(),
+            CHALLENGE,
+            USER_ID,
+            &public_key,
+            stored_count,
+            &AssertionCredential {
+                id: URL_SAFE_NO_PAD.encode(CREDENTIAL_ID),
+                client_data_json: URL_SAFE_NO_PAD.encode(client_data),
+                authenticator_data: URL_SAFE_NO_PAD.encode(authenticator_data),
+                signature: URL_SAFE_NO_PAD.encode(signature.to_der()),
+                user_handle: Some(URL_SAFE_NO_PAD.encode(USER_ID.as_bytes())),
+            },
+        )
+    }
+
+    #[test]
+    fn accepts_a_software_authenticator() {
+        let key = authenticator();
+        let credential = register(&key, &Response::create()).unwrap();
+        assert_eq!(credential.credential_id, CREDENTIAL_ID);
+        assert_eq!(
+            credential.public_key,
+            key.verifying_key().to_encoded_point(false).as_bytes()
+        );
+        assert_eq!(credential.sign_count, 0);
+
+        assert_eq!(sign_in(&key, &Response::get(), 0), Ok(1));
+    }
+
+    #[test]
+    fn rejects_wrong_challenge() {
+        let other = b"some other challenge of 32 bytes";
+        let create = Response { challenge: other, ..Response::create() };
+        let get = Response { challenge: other, ..Response::get() };
+        assert_eq!(register(&authenticator(), &create).unwrap_err(), "challenge mismatch");
+        assert_eq!(sign_in(&authenticator(), &get, 0), Err("challenge mismatch"));
+    }
+
+    #[test]
+    fn rejects_wrong_origin_or_type() {
+        let origin = "https://jakewray.dev.evil.example";
+        let create = Response { origin, ..Response::create() };
+        let get = Response { origin, ..Response::get() };
+        assert_eq!(register(&authenticator(), &create).unwrap_err(), "origin mismatch");
+        assert_eq!(sign_in(&authenticator(), &get, 0), Err("origin mismatch"));
+
+        let create = Response { kind: "webauthn.get", ..Response::create() };
+        let get = Response { kind: "webauthn.create", ..Response::get() };
+        assert_eq!(register(&authenticator(), &create).unwrap_err(), "wrong ceremony type");
+        assert_eq!(sign_in(&authenticator(), &get, 0), Err("wrong ceremony type"));
+    }
+
+    #[test]
+    fn rejects_wrong_rp_id_hash() {
+        let create = Response { rp_id: "evil.example", ..Response::create() };
+        let get = Response { rp_id: "evil.example", ..Response::get() };
+        assert_eq!(register(&authenticator(), &create).unwrap_err(), "RP ID hash mismatch");
+        assert_eq!(sign_in(&authenticator(), &get, 0), Err("RP ID hash mismatch"));
+    }
+
+    #[test]
+    fn rejects_missing_user_presence() {
+        let create = Response { flags: FLAG_USER_VERIFIED, ..Response::create() };
+        let get = Response { flags: FLAG_USER_VERIFIED, ..Response::get() };
+        assert_eq!(register(&authenticator(), &create).unwrap_err(), "user not present");
+        assert_eq!(sign_in(&authenticator(), &get, 0), Err("user not present"));
+    }
+
+    #[test]
+    fn rejects_bad_signature() {
+        let impostor = SigningKey::from_slice(&[0x24; 32]).unwrap();
+        assert_eq!(sign_in(&impostor, &Response::get(), 0), Err("bad signature"));
+    }
+
+    #[test]
+    fn rejects_sign_count_regression() {
+        let key = authenticator();
+        let get = |sign_count| Response { sign_count, ..Response::get() };
+        assert_eq!(sign_in(&key, &get(5), 5), Err("signature counter did not increase"));
+        assert_eq!(sign_in(&key, &get(3), 5), Err("signature counter did not increase"));
+        assert_eq!(sign_in(&key, &get(6), 5), Ok(6));
+        // Synced passkeys never count.
+        assert_eq!(sign_in(&key, &get(0), 0), Ok(0));
+    }
+}
@@ -0,0 +1,276 @@
+//! Account administration from the command line. It goes through the same
+//! `auth::accounts` and `auth::api_token` code as the admin API, so password
+//! rules, the last-owner check and token scopes behave identically.
+//!
+//! Run `admin help` for usage.
+
+use backend::audit::{self, RequestMeta};
+use backend::auth::accounts::{self, UserSummary};
+use backend::auth::api_token;
+use backend::auth::password::PasswordHashing;
+use backend::auth::refresh;
+use dotenvy::dotenv;
+use shared::Role;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+const USAGE: &str = "\
+Usage: admin <command> [arguments]
+
+Commands:
+  list-users
+  create-user <username> [--role owner|editor|contributor|viewer]
+  reset-password <username>
+  disable <username>
+  enable <username>
+  revoke-sessions <username>
+  issue-token <username> <name> [--scope <scope>]... [--expires-days <days>]
+
+Passwords are read from standard input, so they can be piped in. New users
+are viewers unless --role is given. issue-token prints only the token on
+standard output. Uses DATABASE_URL and the ARGON2_* settings from the
+environment or .env, like the server.";
+
+#[tokio::main]
+async fn main() {
+    dotenv().ok();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: &[String]) -> Result<(), String> {
+    let Some((command, rest)) = args.split_first() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+    if matches!(command.as_str(), "help" | "--help" | "-h") {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let pool = connect().await?;
+    match (command.as_str(), rest) {
+        ("list-users", []) => list_users(&pool).await,
+        ("create-user", [username, flags @ ..]) => {
+            let role = match options(flags, &["--role"])?.first() {
+                Some((_, role)) => role.parse()?,
+                None => Role::Viewer,
+            };
+            create_user(&pool, username, role).await
+        }
+        ("reset-password", [username]) => reset_password(&pool, username).await,
+        ("disable", [username]) => set_disabled(&pool, username, true).await,
+        ("enable", [username]) => set_disabled(&pool, username, false).await,
+        ("revoke-sessions", [username]) => revoke_sessions(&pool, username).await,
+        ("issue-token", [username, name, flags @ ..]) => {
+            let mut scopes = Vec::new();
+            let mut expires_days = None;
+            for (flag, value) in options(flags, &["--scope", "--expires-days"])? {
+                if flag == "--scope" {
+                    scopes.push(value);
+                } else {
+                    let days = value
+                        .parse()
+                        .map_err(|_| format!("--expires-days must be a number, not {}", value))?;
+                    expires_days = Some(days);
+                }
+            }
+            issue_token(&pool, username, name, &scopes, expires_days).await
+        }
+        _ => Err(format!("unrecognised command or arguments\n\n{}", USAGE)),
+    }
+}
+
+async fn connect() -> Result<PgPool, String> {
+    let database_url =
+        std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL must be set".to_string())?;
+    PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&database_url)
+        .await
+        .map_err(|e| format!("failed to connect to the database: {}", e))
+}
+
+/// Splits `--flag value` pairs, refusing flags not in `allowed`.
+fn options(args: &[String], allowed: &[&str]) -> Result<Vec<(String, String)>, String> {
+    let mut pairs = Vec::new();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if !allowed.contains(&flag.as_str()) {
+            return Err(format!("unexpected argument {}\n\n{}", flag, USAGE));
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        pairs.push((flag.clone(), value.clone()));
+    }
+    Ok(pairs)
+}
+
+async fn find(pool: &PgPool, username: &str) -> Result<UserSummary, String> {
+    accounts::find_by_username(pool, username)
+        .await
+        .map_err(|e| format!("{}: {}", username, e))
+}
+
+async fn list_users(pool: &PgPool) -> Result<(), String> {
+    let users = accounts::list(pool).await.map_err(|e| e.to_string())?;
+    println!("{:<24} {:<12} {:<4} {:<9} ID", "USERNAME", "ROLE", "2FA", "STATUS");
+    for user in users {
+        println!(
+            "{:<24} {:<12} {:<4} {:<9} {}",
+            user.username,
+            user.role.as_str(),
+            if user.two_factor { "on" } else { "off" },
+            if user.disabled { "disabled" } else { "active" },
+            user.id
+        );
+    }
+    Ok(())
+}
+
+async fn create_user(pool: &PgPool, username: &str, role: Role) -> Result<(), String> {
+    let passwords = PasswordHashing::from_env()?;
+    let password = read_password()?;
+    let id = accounts::create(pool, &passwords, username, password, role)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    audit::Entry::new("user.created")
+        .target("user", id)
+        .details(serde_json::json!({ "username": username.trim(), "role": role }))
+        .record(pool, &RequestMeta::cli())
+        .await;
+    eprintln!("Created {} as {}", username.trim(), role.as_str());
+    Ok(())
+}
+
+async fn reset_password(pool: &PgPool, username: &str) -> Result<(), String> {
+    let passwords = PasswordHashing::from_env()?;
+    let user = find(pool, username).await?;
+    let password = read_password()?;
+    accounts::reset_password(pool, &passwords, user.id, password)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    audit::Entry::new("user.updated")
+        .target("user", user.id)
+        .details(serde_json::json!({ "password_reset": true }))
+        .record(pool, &RequestMeta::cli())
+        .await;
+    eprintln!("Reset the password of {} and signed them out everywhere", user.username);
+    Ok(())
+}
+
+async fn set_disabled(pool: &PgPool, username: &str, disabled: bool) -> Result<(), String> {
+    let user = find(pool, username).await?;
+    accounts::set_disabled(pool, user.id, disabled)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    audit::Entry::new("user.updated")
+        .target("user", user.id)
+        .details(audit::diff(
+            &serde_json::json!({ "disabled": user.disabled }),
+            &serde_json::json!({ "disabled": disabled }),
+        ))
+        .record(pool, &RequestMeta::cli())
+        .await;
+    eprintln!(
+        "{} {}",
+        if disabled { "Disabled" } else { "Enabled" },
+        user.username
+    );
+    Ok(())
+}
+
+async fn revoke_sessions(pool: &PgPool, username: &str) -> Result<(), String> {
+    let user = find(pool, username).await?;
+    refresh::revoke_everything(pool, user.id)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    audit::Entry::new("auth.logout_all")
+        .target("user", user.id)
+        .record(pool, &RequestMeta::cli())
+        .await;
+    eprintln!("Signed {} out of every session and device", user.username);
+    Ok(())
+}
+
+async fn issue_token(
+    pool: &PgPool,
+    username: &str,
+    name: &str,
+    scopes: &[String],
+    expires_days: Option<i64>,
+) -> Result<(), String> {
+    let user = find(pool, username).await?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Token name is required".to_string());
+    }
+    let scopes = api_token::parse_scopes(user.role, scopes)?;
+    let expires_at = api_token::expiry(expires_days)?;
+
+    let (details, token) = api_token::create(pool, user.id, name, &scopes, expires_at)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    audit::Entry::new("token.created")
+        .target("api_token", details.id)
+        .details(serde_json::json!({ "name": name, "scopes": details.scopes, "user": user.username }))
+        .record(pool, &RequestMeta::cli())
+        .await;
+    eprintln!(
+        "Issued token {} for {} with scopes [{}]; it won't be shown again",
+        details.id,
+        user.username,
+        details.scopes.join(", ")
+    );
+    println!("{}", token);
+    Ok(())
+}
+
+/// Reads a password from standard input. On a terminal it prompts twice with
+/// echo turned off; otherwise it takes the first line, for piping.
+fn read_password() -> Result<String, String> {
+    if !io::stdin().is_terminal() {
+        let mut line = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .map_err(|e| format!("failed to read password: {}", e))?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    let password = prompt_hidden("Password: ")?;
+    if prompt_hidden("Repeat password: ")? != password {
+        return Err("passwords don't match".to_string());
+    }
+    Ok(password)
+}
+
+fn prompt_hidden(prompt: &str) -> Result<String, String> {
+    eprint!("{}", prompt);
+    io::stderr().flush().ok();
+
+    set_echo(false);
+    let mut line = String::new();
+    let read = io::stdin().lock().read_line(&mut line);
+    set_echo(true);
+    eprintln!();
+
+    read.map_err(|e| format!("failed to read password: {}", e))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Hides typed characters on Unix terminals; a no-op where `stty` is missing.
+fn set_echo(on: bool) {
+    let _ = std::process::Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(std::process::Stdio::inherit())
+        .status();
+}
@@ -0,0 +1,359 @@
+//! HTTP caching, configured in one place: the `Cache-Control` policy for each
+//! class of route, and the validators (`ETag`, `Last-Modified`) that let
+//! clients revalidate with a `304 Not Modified` instead of a full response.
+//!
+//! Pages and API routes that show articles or blog posts get a weak `ETag`
+//! derived from the content's timestamps, checked before anything is queried
+//! or rendered. Other successful `GET`s of data whose length is known and at
+//! most [`MAX_HASHED_BODY`] get a strong `ETag` hashed from the body, which
+//! saves the transfer if not the work. Other pages are streamed as rendered,
+//! without validators.
+
+use axum::body::{to_bytes, Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+/// How long site files without a content hash (favicons, images, and `/pkg`
+/// when hashing is off) may be reused.
+const ASSET_MAX_AGE: u32 = 24 * 60 * 60;
+
+/// How long hashed files may be reused: a year, the conventional "forever".
+const IMMUTABLE_MAX_AGE: u32 = 365 * 24 * 60 * 60;
+
+/// The largest data response buffered to hash an `ETag` from its body.
+pub const MAX_HASHED_BODY: u64 = 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    /// `max-age` of server-rendered pages, in seconds.
+    pub page_max_age: u32,
+    /// `max-age` of the public API, feeds and the sitemap, in seconds.
+    pub data_max_age: u32,
+    /// Whether `/pkg` file names carry a content hash (cargo-leptos'
+    /// `hash-files`), so a changed file always has a new URL.
+    pub hashed_assets: bool,
+    /// Mixed into content-derived ETags so a deploy, which may change how
+    /// the same content renders, invalidates them: the `GIT_SHA` the binary
+    /// was built with, or else the crate version.
+    build: &'static str,
+}
+
+/// What a response may be cached as. See [`CacheConfig::policy`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Policy {
+    /// Content-hashed build output under `/pkg`.
+    Immutable,
+    /// Other files from the site root.
+    Asset,
+    /// Server-rendered HTML.
+    Page,
+    /// The public API, server functions, feeds, the sitemap and `robots.txt`.
+    Data,
+    /// The admin, which only the signed-in browser may keep, and not on disk.
+    Private,
+    /// Health checks and failed responses.
+    NoStore,
+}
+
+impl CacheConfig {
+    /// Reads `CACHE_PAGE_MAX_AGE` and `CACHE_DATA_MAX_AGE`, in seconds (both
+    /// default to 60).
+    pub fn from_env() -> Result<Self, String> {
+        let read = |name: &str, default: u32| -> Result<u32, String> {
+            match std::env::var(name) {
+                Ok(v) => v.parse().map_err(|e| format!("Invalid {}: {}", name, e)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        Ok(Self {
+            page_max_age: read("CACHE_PAGE_MAX_AGE", 60)?,
+            data_max_age: read("CACHE_DATA_MAX_AGE", 60)?,
+            hashed_assets: false,
+            build: option_env!("GIT_SHA")
+                .filter(|sha| !sha.is_empty())
+                .unwrap_or(env!("CARGO_PKG_VERSION")),
+        })
+    }
+
+    /// Records whether `/pkg` files are hashed, from `LeptosOptions::hash_files`.
+    pub fn with_hashed_assets(mut self, hashed: bool) -> Self {
+        self.hashed_assets = hashed;
+        self
+    }
+
+    /// The policy for a successful response to `path`. Decided from the path
+    /// alone so that a `304` carries the same policy as the `200` it stands
+    /// in for.
+    pub fn policy(&self, path: &str) -> Policy {
+        let file_name = path.rsplit('/').next().unwrap_or_default();
+        if path == "/admin" || path.starts_with("/admin/") {
+            Policy::Private
+        } else if path == "/health" {
+            Policy::NoStore
+        } else if path.starts_with("/pkg/") {
+            if self.hashed_assets {
+                Policy::Immutable
+            } else {
+                Policy::Asset
+            }
+        } else if path.starts_with("/api/")
+            || file_name.ends_with(".xml")
+            || file_name.ends_with(".json")
+            || file_name.ends_with(".txt")
+        {
+            Policy::Data
+        } else if file_name.contains('.') {
+            Policy::Asset
+        } else {
+            Policy::Page
+        }
+    }
+
+    /// The `Cache-Control` value for `policy`. `shared` is false when the
+    /// response sets a cookie, which a proxy must not hand to anyone else.
+    pub fn cache_control(&self, policy: Policy, shared: bool) -> String {
+        let scope = if shared { "public" } else { "private" };
+        match policy {
+            Policy::Immutable => format!("{}, max-age={}, immutable", scope, IMMUTABLE_MAX_AGE),
+            Policy::Asset => format!("{}, max-age={}", scope, ASSET_MAX_AGE),
+            Policy::Page => format!("{}, max-age={}", scope, self.page_max_age),
+            Policy::Data => format!("{}, max-age={}", scope, self.data_max_age),
+            Policy::Private => "private, no-store".to_string(),
+            Policy::NoStore => "no-store".to_string(),
+        }
+    }
+
+    /// A weak `ETag` for content last changed at `updated` and made of
+    /// `count` items, as served at `target` (path and query).
+    fn content_etag(&self, target: &str, updated: DateTime<Utc>, count: i64) -> String {
+        let key = format!("{}\n{}\n{}\n{}", self.build, target, updated.timestamp_micros(), count);
+        format!("W/\"{}\"", URL_SAFE_NO_PAD.encode(Sha256::digest(key.as_bytes())))
+    }
+}
+
+/// A strong `ETag` for `body`.
+pub fn body_etag(body: &[u8]) -> String {
+    format!("\"{}\"", URL_SAFE_NO_PAD.encode(Sha256::digest(body)))
+}
+
+/// Whether a client holding `etag` or `last_modified` can reuse its copy.
+/// `If-None-Match` takes precedence over `If-Modified-Since`, and uses the
+/// weak comparison, so `W/"x"` matches `"x"`.
+pub fn not_modified(
+    headers: &HeaderMap,
+    etag: Option<&str>,
+    last_modified: Option<DateTime<Utc>>,
+) -> bool {
+    if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+        let Some(etag) = etag else {
+            return false;
+        };
+        let opaque = |tag: &str| tag.strip_prefix("W/").unwrap_or(tag).to_string();
+        let etag = opaque(etag);
+        if_none_match.to_str().is_ok_and(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || opaque(tag) == etag)
+        })
+    } else if let (Some(since), Some(last_modified)) =
+        (headers.get(header::IF_MODIFIED_SINCE), last_modified)
+    {
+        since
+            .to_str()
+            .ok()
+            .and_then(|since| DateTime::parse_from_rfc2822(since).ok())
+            .is_some_and(|since| whole_seconds(last_modified) <= since)
+    } else {
+        false
+    }
+}
+
+/// Sets `ETag` and `Last-Modified` on a response.
+pub fn set_validators(
+    headers: &mut HeaderMap,
+    etag: Option<&str>,
+    last_modified: Option<DateTime<Utc>>,
+) {
+    if let Some(etag) = etag.and_then(|etag| HeaderValue::from_str(etag).ok()) {
+        headers.insert(header::ETAG, etag);
+    }
+    if let Some(last_modified) = last_modified {
+        let value = whole_seconds(last_modified)
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(header::LAST_MODIFIED, value);
+        }
+    }
+}
+
+/// `body` as `content_type` with a strong `ETag` and `Last-Modified`, or a
+/// bare `304` when the client's copy is still current.
+pub fn conditional(
+    headers: &HeaderMap,
+    content_type: &'static str,
+    body: String,
+    last_modified: Option<DateTime<Utc>>,
+) -> Response {
+    let etag = body_etag(body.as_bytes());
+    let mut response = if not_modified(headers, Some(&etag), last_modified) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let mut response = Response::new(Body::from(body));
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        response
+    };
+    set_validators(response.headers_mut(), Some(&etag), last_modified);
+    response
+}
+
+/// HTTP dates have whole-second precision.
+fn whole_seconds(at: DateTime<Utc>) -> DateTime<Utc> {
+    DateTime::from_timestamp(at.timestamp(), 0).unwrap_or(at)
+}
+
+/// When the articles or blog posts behind `path` last changed, and how many
+/// there are, so that deleting or scheduling one changes the result too.
+/// `None` for paths that don't show them, or an item that doesn't exist.
+async fn content_version(
+    pool: &PgPool,
+    path: &str,
+) -> Result<Option<(DateTime<Utc>, i64)>, sqlx::Error> {
+    const ARTICLES: &str = "SELECT MAX(updated_at), COUNT(*) FROM articles";
+    const ARTICLE: &str = "SELECT MAX(updated_at), COUNT(*) FROM articles WHERE slug = $1";
+    const POSTS: &str = "SELECT MAX(GREATEST(updated_at, published_at)), COUNT(*) \
+        FROM blog_posts WHERE published_at <= NOW()";
+    const POST: &str = "SELECT MAX(GREATEST(updated_at, published_at)), COUNT(*) \
+        FROM blog_posts WHERE published_at <= NOW() AND slug = $1";
+
+    let item = |prefix: &str| {
+        path.strip_prefix(prefix)
+            .filter(|slug| !slug.is_empty() && !slug.contains('/'))
+    };
+    let query = match path {
+        "/journalism" | "/api/articles" => sqlx::query_as(ARTICLES),
+        "/blog" | "/api/blog" => sqlx::query_as(POSTS),
+        _ => {
+            if let Some(slug) = item("/journalism/").or_else(|| item("/api/articles/")) {
+                sqlx::query_as(ARTICLE).bind(slug)
+            } else if let Some(slug) = item("/blog/").or_else(|| item("/api/blog/")) {
+                sqlx::query_as(POST).bind(slug)
+            } else {
+                return Ok(None);
+            }
+        }
+    };
+    let (updated, count): (Option<DateTime<Utc>>, i64) = query.fetch_one(pool).await?;
+    Ok(updated.map(|updated| (updated, count)))
+}
+
+/// Applies the caching policy to every response: answers conditional
+/// requests for content-backed routes without running them, adds validators
+/// to their successful responses and to small `GET`s of data, and sets
+/// `Cache-Control` unless the handler already did.
+pub async fn control(
+    State(config): State<CacheConfig>,
+    State(pool): State<PgPool>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return next.run(req).await;
+    }
+    let path = req.uri().path().to_string();
+    let policy = config.policy(&path);
+
+    let mut validators = None;
+    if matches!(policy, Policy::Page | Policy::Data) {
+        match content_version(&pool, &path).await {
+            Ok(Some((updated, count))) => {
+                let target = req.uri().path_and_query().map_or(path.as_str(), |pq| pq.as_str());
+                validators = Some((config.content_etag(target, updated, count), updated));
+            }
+            Ok(None) => {}
+            // Serve the page uncached rather than fail it.
+            Err(e) => tracing::error!("Failed to look up content version of {}: {}", path, e),
+        }
+    }
+
+    let mut response = match &validators {
+        Some((etag, updated)) if not_modified(req.headers(), Some(etag), Some(*updated)) => {
+            StatusCode::NOT_MODIFIED.into_response()
+        }
+        _ => {
+            let is_get = req.method() == Method::GET;
+            let conditions = req.headers().clone();
+            let response = next.run(req).await;
+            let length = response
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok()?.parse().ok())
+                .or_else(|| response.body().size_hint().exact());
+            if response.status() != StatusCode::OK
+                || validators.is_some()
+                || !is_get
+                || policy != Policy::Data
+                || length.is_none_or(|length| length > MAX_HASHED_BODY)
+                || response.headers().contains_key(header::ETAG)
+                || response.headers().contains_key(header::LAST_MODIFIED)
+            {
+                response
+            } else {
+                // HEAD bodies are stripped before they get here, so only GETs
+                // can be hashed.
+                let (mut parts, body) = response.into_parts();
+                let bytes = match to_bytes(body, MAX_HASHED_BODY as usize).await {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        tracing::error!("Failed to buffer response to {}: {}", path, e);
+                        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                    }
+                };
+                let etag = body_etag(&bytes);
+                set_validators(&mut parts.headers, Some(&etag), None);
+                if not_modified(&conditions, Some(&etag), None) {
+                    let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+                    for name in [header::ETAG, header::SET_COOKIE] {
+                        for value in parts.headers.get_all(&name) {
+                            not_modified.headers_mut().append(&name, value.clone());
+                        }
+                    }
+                    not_modified
+                } else {
+                    Response::from_parts(parts, Body::from(bytes))
+                }
+            }
+        }
+    };
+
+    let status = response.status();
+    let fresh = status.is_success() || status == StatusCode::NOT_MODIFIED;
+    if let Some((etag, updated)) = validators.filter(|_| fresh) {
+        if !response.headers().contains_key(header::ETAG) {
+            set_validators(response.headers_mut(), Some(&etag), Some(updated));
+        }
+    }
+    if !response.headers().contains_key(header::CACHE_CONTROL) {
+        let policy = if fresh || policy == Policy::Private {
+            policy
+        } else {
+            Policy::NoStore
+        };
+        let shared = !response.headers().contains_key(header::SET_COOKIE);
+        if let Ok(value) = HeaderValue::from_str(&config.cache_control(policy, shared)) {
+            response.headers_mut().insert(header::CACHE_CONTROL, value);
+        }
+    }
+    response
+}
@@ -0,0 +1,10 @@
+//! Modules shared by the web server (`main.rs`) and the `admin` CLI
+//! (`bin/admin.rs`).
+
+pub mod api;
+pub mod audit;
+pub mod auth;
+pub mod cache;
+pub mod error;
+pub mod site;
+pub mod state;
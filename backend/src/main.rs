@@ -1,10 +1,11 @@
-use axum::{extract::State, Router};
+use axum::{extract::State, routing, Router};
 use axum::body::Body;
 use axum::http::Request;
 use axum::middleware::{self, Next};
 use bytes::Bytes;
 use dotenvy::dotenv;
 use frontend::App;
+use frontend::components::seo::SiteUrl;
 use futures_util::stream;
 use futures_util::StreamExt;
 use leptos::context::provide_context;
@@ -15,10 +16,8 @@ use std::net::SocketAddr;
 use tower::ServiceBuilder;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-mod api;
-mod state;
-
-use crate::state::AppState;
+use backend::state::AppState;
+use backend::{api, auth, cache, error, site};
 use axum::response::{IntoResponse, Response as AxumResponse};
 use tower::ServiceExt;
 
@@ -71,39 +70,82 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3001),
         )
+        .hash_files(std::env::var("LEPTOS_HASH_FILES").is_ok_and(|v| v == "true"))
         .build();
 
+    let passwords = auth::password::PasswordHashing::from_env()?;
+    let keys = auth::keys::Keyring::from_env()?;
+    let sessions = auth::session::SessionConfig::from_env()?;
+    let throttle = auth::throttle::LoginThrottle::from_env()?;
+    let webauthn = auth::webauthn::WebauthnConfig::from_env()?;
+
     let addr = leptos_options.site_addr;
     let routes = generate_route_list(App);
+    let site = site::SiteConfig::from_env()?.with_routes(routes.iter().map(|r| r.path()));
+    let cache = cache::CacheConfig::from_env()?.with_hashed_assets(leptos_options.hash_files);
 
     let app_state = AppState {
         leptos_options: leptos_options.clone(),
         pool: pool.clone(),
+        passwords,
+        keys,
+        sessions,
+        throttle,
+        webauthn,
+        site,
+        cache,
     };
 
     // Build the application router with all routes
     let app = Router::new()
         .nest("/", api::router(app_state.clone()))
+        .route(
+            "/api/*fn_name",
+            routing::get(server_fn_handler).post(server_fn_handler),
+        )
         .leptos_routes_with_context(
             &app_state,
             routes,
             {
                 let pool = app_state.pool.clone();
                 let options = app_state.leptos_options.clone();
+                let site_url = SiteUrl(app_state.site.base_url.clone());
                 move || {
                     provide_context(pool.clone());
                     provide_context(options.clone());
+                    provide_context(site_url.clone());
                 }
             },
             App,
         )
         .fallback(file_and_error_handler)
-        .layer(ServiceBuilder::new().layer(middleware::from_fn(inject_doctype)))
+        .layer(
+            ServiceBuilder::new()
+                .layer(middleware::from_fn(error::correlate))
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    cache::control,
+                ))
+                .layer(middleware::from_fn(inject_doctype))
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth::session::guard_admin_pages,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth::csrf::protect,
+                )),
+        )
         .with_state(app_state);
 
     tracing::info!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app.into_make_service()).await?;
+    // Login throttling needs the peer address to key attempts by client IP.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
@@ -131,6 +173,18 @@ async fn inject_doctype(
     Ok(res.into_response())
 }
 
+/// Runs the frontend's server functions, e.g. the journalism page loaders.
+async fn server_fn_handler(State(state): State<AppState>, req: Request<Body>) -> AxumResponse {
+    leptos_axum::handle_server_fns_with_context(
+        move || {
+            provide_context(state.pool.clone());
+        },
+        req,
+    )
+    .await
+    .into_response()
+}
+
 async fn file_and_error_handler(
     State(state): State<AppState>,
     uri: axum::http::Uri,
@@ -146,6 +200,7 @@ async fn file_and_error_handler(
             move || {
                 provide_context(state.leptos_options.clone());
                 provide_context(state.pool.clone());
+                provide_context(SiteUrl(state.site.base_url.clone()));
             },
             App,
         );
@@ -0,0 +1,69 @@
+/// Where the site is publicly served, for anything that needs absolute URLs
+/// (feeds, the sitemap, canonical links), and what crawlers may index.
+#[derive(Clone, Debug)]
+pub struct SiteConfig {
+    /// Scheme and host without a trailing slash, e.g. `https://jakewray.dev`.
+    pub base_url: String,
+    /// Path prefixes `robots.txt` asks crawlers to stay out of. Always
+    /// includes `/admin`.
+    pub disallow: Vec<String>,
+    /// The app's routes without parameters, e.g. `/journalism`, minus
+    /// disallowed ones. Filled in by [`SiteConfig::with_routes`].
+    pub pages: Vec<String>,
+}
+
+impl SiteConfig {
+    /// Reads `SITE_URL` (default `http://localhost:3000`) and
+    /// `ROBOTS_DISALLOW`, a comma-separated list of extra path prefixes to
+    /// keep crawlers out of (`/` for a staging site that shouldn't be indexed
+    /// at all).
+    pub fn from_env() -> Result<Self, String> {
+        let base_url =
+            std::env::var("SITE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+        let base_url = base_url.trim_end_matches('/').to_string();
+        if !(base_url.starts_with("https://") || base_url.starts_with("http://")) {
+            return Err(format!("SITE_URL must start with http:// or https://: {}", base_url));
+        }
+
+        let mut disallow = vec!["/admin".to_string()];
+        for path in std::env::var("ROBOTS_DISALLOW").unwrap_or_default().split(',') {
+            let path = path.trim();
+            if path.is_empty() || disallow.iter().any(|p| p == path) {
+                continue;
+            }
+            if !path.starts_with('/') {
+                return Err(format!("ROBOTS_DISALLOW paths must start with /: {}", path));
+            }
+            disallow.push(path.to_string());
+        }
+
+        Ok(Self {
+            base_url,
+            disallow,
+            pages: Vec::new(),
+        })
+    }
+
+    /// Records the static pages among `routes` for the sitemap.
+    pub fn with_routes<'a>(mut self, routes: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut pages: Vec<String> = routes
+            .into_iter()
+            .filter(|path| !path.contains([':', '*']) && self.allows(path))
+            .map(str::to_string)
+            .collect();
+        pages.sort();
+        pages.dedup();
+        self.pages = pages;
+        self
+    }
+
+    /// The absolute URL of `path`, which must start with `/`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Whether `robots.txt` lets crawlers fetch `path`.
+    pub fn allows(&self, path: &str) -> bool {
+        !self.disallow.iter().any(|prefix| path.starts_with(prefix.as_str()))
+    }
+}
@@ -1,3 +1,10 @@
+use crate::auth::keys::Keyring;
+use crate::auth::password::PasswordHashing;
+use crate::auth::session::SessionConfig;
+use crate::auth::throttle::LoginThrottle;
+use crate::auth::webauthn::WebauthnConfig;
+use crate::cache::CacheConfig;
+use crate::site::SiteConfig;
 use axum::extract::FromRef;
 use leptos::prelude::LeptosOptions;
 use sqlx::PgPool;
@@ -6,6 +13,13 @@ use sqlx::PgPool;
 pub struct AppState {
     pub leptos_options: LeptosOptions,
     pub pool: PgPool,
+    pub passwords: PasswordHashing,
+    pub keys: Keyring,
+    pub sessions: SessionConfig,
+    pub throttle: LoginThrottle,
+    pub webauthn: WebauthnConfig,
+    pub site: SiteConfig,
+    pub cache: CacheConfig,
 }
 
 impl FromRef<AppState> for LeptosOptions {
@@ -19,3 +33,45 @@ impl FromRef<AppState> for PgPool {
         state.pool.clone()
     }
 }
+
+impl FromRef<AppState> for PasswordHashing {
+    fn from_ref(state: &AppState) -> Self {
+        state.passwords.clone()
+    }
+}
+
+impl FromRef<AppState> for Keyring {
+    fn from_ref(state: &AppState) -> Self {
+        state.keys.clone()
+    }
+}
+
+impl FromRef<AppState> for SessionConfig {
+    fn from_ref(state: &AppState) -> Self {
+        state.sessions.clone()
+    }
+}
+
+impl FromRef<AppState> for LoginThrottle {
+    fn from_ref(state: &AppState) -> Self {
+        state.throttle.clone()
+    }
+}
+
+impl FromRef<AppState> for WebauthnConfig {
+    fn from_ref(state: &AppState) -> Self {
+        state.webauthn.clone()
+    }
+}
+
+impl FromRef<AppState> for SiteConfig {
+    fn from_ref(state: &AppState) -> Self {
+        state.site.clone()
+    }
+}
+
+impl FromRef<AppState> for CacheConfig {
+    fn from_ref(state: &AppState) -> Self {
+        state.cache.clone()
+    }
+}
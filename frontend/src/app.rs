@@ -1,11 +1,16 @@
 use crate::components::footer::Footer;
 use crate::components::navbar::Navbar;
+use crate::components::structured_data::{person, JsonLd};
+use crate::pages::admin::audit::AdminAudit;
 use crate::pages::admin::composer::AdminComposer;
 use crate::pages::admin::dashboard::AdminDashboard;
 use crate::pages::admin::login::AdminLoginPage;
 use crate::pages::admin::sync_manager::AdminSyncManager;
+use crate::pages::admin::tokens::AdminTokens;
+use crate::pages::admin::users::AdminUsers;
+use crate::pages::search::SearchPage;
 use crate::pages::sections::{
-    JournalismArticlePage, JournalismPage, PersonalBlogPage, ProgrammingPage,
+    BlogPostPage, JournalismArticlePage, JournalismPage, PersonalBlogPage, ProgrammingPage,
 };
 use leptos::prelude::*;
 use leptos_meta::*;
@@ -17,7 +22,7 @@ use leptos_router::hooks::use_location;
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
-    let _ = use_context::<leptos::config::LeptosOptions>().unwrap_or_else(|| {
+    let options = use_context::<leptos::config::LeptosOptions>().unwrap_or_else(|| {
         // Fallback for contexts that don't inject options (e.g., route list gen / client mount).
         // Matches defaults used in backend when env vars are absent.
         leptos::config::LeptosOptions::builder()
@@ -34,13 +39,18 @@ pub fn App() -> impl IntoView {
             <head>
                 <Meta charset="utf-8"/>
                 <Meta name="viewport" content="width=device-width, initial-scale=1"/>
-                <Meta name="description" content="Journalist, Programmer, Photographer."/>
                 <Title text="Jake Wray"/>
-                <Stylesheet id="leptos" href="/pkg/jakewray_ca.css"/>
+                <HashedStylesheet options id="leptos"/>
+                <Link rel="alternate" type_="application/rss+xml" title="Jake Wray" href="/feed.xml"/>
+                <Link rel="alternate" type_="application/atom+xml" title="Jake Wray" href="/atom.xml"/>
+                <Link rel="alternate" type_="application/feed+json" title="Jake Wray" href="/feed.json"/>
             </head>
 
             <body>
+                <JsonLd data=person()/>
                 <Router>
+                    <DefaultDescription/>
+                    <SectionFeedLinks/>
                     <MainLayout/>
 
                 </Router>
@@ -49,6 +59,41 @@ pub fn App() -> impl IntoView {
     }
 }
 
+/// The site-wide description, left out on article and post pages, which
+/// describe themselves through `PageMeta`.
+#[component]
+fn DefaultDescription() -> impl IntoView {
+    let location = use_location();
+    move || {
+        let path = location.pathname.get();
+        let is_detail = ["/journalism/", "/blog/"]
+            .iter()
+            .any(|prefix| path.strip_prefix(prefix).is_some_and(|slug| !slug.is_empty()));
+        (!is_detail).then(|| view! { <Meta name="description" content="Journalist, Programmer, Photographer."/> })
+    }
+}
+
+/// Advertises the journalism or blog feeds alongside the site-wide ones while
+/// browsing that section.
+#[component]
+fn SectionFeedLinks() -> impl IntoView {
+    let location = use_location();
+    move || {
+        let path = location.pathname.get();
+        let (prefix, title) = if path.starts_with("/journalism") {
+            ("/journalism", "Jake Wray — Journalism")
+        } else if path.starts_with("/blog") {
+            ("/blog", "Jake Wray — Blog")
+        } else {
+            return None;
+        };
+        Some(view! {
+            <Link rel="alternate" type_="application/rss+xml" title=title href=format!("{}/feed.xml", prefix)/>
+            <Link rel="alternate" type_="application/atom+xml" title=title href=format!("{}/atom.xml", prefix)/>
+            <Link rel="alternate" type_="application/feed+json" title=title href=format!("{}/feed.json", prefix)/>
+        })
+    }
+}
 
 #[component]
 fn MainLayout() -> impl IntoView {
@@ -79,7 +124,9 @@ fn MainLayout() -> impl IntoView {
                     <Route path=path!("/journalism") view=JournalismPage/>
                     <Route path=path!("/journalism/:slug") view=JournalismArticlePage/>
                     <Route path=path!("/blog") view=PersonalBlogPage/>
+                    <Route path=path!("/blog/:slug") view=BlogPostPage/>
                     <Route path=path!("/about") view=DummyPage/>
+                    <Route path=path!("/search") view=SearchPage/>
 
                     // Admin Routes
                     <Route path=path!("/admin") view=AdminRedirect/>
@@ -87,6 +134,9 @@ fn MainLayout() -> impl IntoView {
                     <Route path=path!("/admin/login") view=AdminLoginPage/>
                     <Route path=path!("/admin/compose") view=AdminComposer/>
                     <Route path=path!("/admin/sync") view=AdminSyncManager/>
+                    <Route path=path!("/admin/users") view=AdminUsers/>
+                    <Route path=path!("/admin/audit") view=AdminAudit/>
+                    <Route path=path!("/admin/tokens") view=AdminTokens/>
                     <Route path=path!("/admin/media") view=MediaLibraryPlaceholder/>
                 </Routes>
             </main>
@@ -102,11 +152,8 @@ fn SectionLayout() -> impl IntoView {
 
 #[component]
 fn AdminRedirect() -> impl IntoView {
-    let navigate = leptos_router::hooks::use_navigate();
-    leptos::prelude::Effect::new(move || {
-        navigate("/admin/login", Default::default());
-    });
-    view! {}
+    // Signed-out visitors are sent to the login page by the server before this renders.
+    view! { <Redirect path="/admin/dashboard"/> }
 }
 
 #[component]
@@ -0,0 +1,40 @@
+use leptos::prelude::*;
+
+/// The CSRF token to echo back with state-changing requests. During SSR it
+/// comes from the request (the backend may have just minted it); in the
+/// browser it is read from the cookie the backend set.
+#[cfg(feature = "ssr")]
+pub fn token() -> String {
+    use_context::<http::request::Parts>()
+        .and_then(|parts| parts.extensions.get::<shared::CsrfToken>().cloned())
+        .map(|token| token.0)
+        .unwrap_or_default()
+}
+
+#[cfg(all(not(feature = "ssr"), target_arch = "wasm32"))]
+pub fn token() -> String {
+    use wasm_bindgen::JsCast;
+
+    let cookies = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.dyn_into::<web_sys::HtmlDocument>().ok())
+        .and_then(|d| d.cookie().ok())
+        .unwrap_or_default();
+    cookies
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == shared::CSRF_COOKIE)
+        .map(|(_, value)| value.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(all(not(feature = "ssr"), not(target_arch = "wasm32")))]
+pub fn token() -> String {
+    String::new()
+}
+
+/// Hidden input carrying the CSRF token; put one in every `method="post"` form.
+#[component]
+pub fn CsrfField() -> impl IntoView {
+    view! { <input type="hidden" name=shared::CSRF_FIELD value=token()/> }
+}
@@ -0,0 +1,32 @@
+use leptos::prelude::*;
+use pulldown_cmark::{html, Event, Options, Parser, Tag};
+
+/// Renders Markdown to HTML. Raw HTML in the source is shown as text rather
+/// than injected into the page.
+pub fn to_html(source: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_SMART_PUNCTUATION;
+    let events = Parser::new_ext(source, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        other => other,
+    });
+
+    let mut out = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+/// The address of the first image in `source`, if it has one.
+pub fn first_image(source: &str) -> Option<String> {
+    Parser::new(source).find_map(|event| match event {
+        Event::Start(Tag::Image { dest_url, .. }) => Some(dest_url.to_string()),
+        _ => None,
+    })
+}
+
+#[component]
+pub fn Markdown(#[prop(into)] source: String) -> impl IntoView {
+    view! { <div class="article-content prose" inner_html=to_html(&source)></div> }
+}
@@ -1,2 +1,7 @@
+pub mod csrf;
 pub mod footer;
+pub mod markdown;
 pub mod navbar;
+pub mod problem;
+pub mod seo;
+pub mod structured_data;
@@ -1,10 +1,19 @@
 use leptos::prelude::*;
-use leptos_router::components::A;
-use leptos_router::hooks::use_location;
+use leptos_router::components::{Form, A};
+use leptos_router::hooks::{use_location, use_query_map};
 
 #[component]
 pub fn Navbar() -> impl IntoView {
     let location = use_location();
+    let query = use_query_map();
+    // Keeps the box filled in while looking at results.
+    let current_query = move || {
+        if location.pathname.get() == "/search" {
+            query.with(|q| q.get("q")).unwrap_or_default()
+        } else {
+            String::new()
+        }
+    };
     let is_active = move |path: &'static str| {
         move || {
             if location.pathname.get().starts_with(path) {
@@ -28,6 +37,16 @@ pub fn Navbar() -> impl IntoView {
                     <A href="/journalism" attr:class=is_active("/journalism")>"Journalism"</A>
                     <A href="/about" attr:class=is_active("/about")>"About Me"</A>
                 </nav>
+
+                <Form action="/search" method="get" attr:class="nav-search" attr:role="search">
+                    <input
+                        type="search"
+                        name="q"
+                        placeholder="Search"
+                        aria-label="Search"
+                        prop:value=current_query
+                    />
+                </Form>
             </div>
         </header>
     }
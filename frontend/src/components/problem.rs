@@ -0,0 +1,9 @@
+/// The message to show for a failed API response: the `detail` of its
+/// problem+json body, or the raw body when it isn't one.
+#[cfg(target_arch = "wasm32")]
+pub async fn message(resp: &gloo_net::http::Response) -> String {
+    let body = resp.text().await.unwrap_or_default();
+    serde_json::from_str::<shared::Problem>(&body)
+        .map(|problem| problem.detail)
+        .unwrap_or(body)
+}
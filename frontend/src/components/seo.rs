@@ -0,0 +1,133 @@
+//! Per-page `<head>` metadata: title, description, canonical link, and the
+//! OpenGraph and Twitter card tags link previews are built from.
+
+use leptos::prelude::*;
+use leptos_meta::{Link, Meta, Title};
+
+/// The site's public origin, e.g. `https://jakewray.dev`, provided by the
+/// server so meta tags rendered during SSR carry absolute URLs.
+#[derive(Clone, Debug)]
+pub struct SiteUrl(pub String);
+
+/// The longest description we emit; search results truncate around here.
+const MAX_DESCRIPTION: usize = 200;
+
+/// `path` as an absolute URL. Absolute URLs are returned unchanged.
+pub fn absolute_url(path: &str) -> String {
+    if path.starts_with("https://") || path.starts_with("http://") {
+        return path.to_string();
+    }
+    let base = use_context::<SiteUrl>()
+        .map(|site| site.0)
+        .or_else(browser_origin)
+        .unwrap_or_default();
+    format!("{}{}", base, path)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn browser_origin() -> Option<String> {
+    web_sys::window()?.location().origin().ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn browser_origin() -> Option<String> {
+    None
+}
+
+/// Turns an HTML fragment into a one-line description: tags dropped, common
+/// entities decoded, whitespace collapsed, and cut at a word boundary.
+pub fn describe(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    let text = decode_entities(&text);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_DESCRIPTION {
+        return text;
+    }
+    let cut: String = text.chars().take(MAX_DESCRIPTION).collect();
+    let cut = cut.rsplit_once(' ').map(|(head, _)| head).unwrap_or(&cut);
+    format!("{}…", cut.trim_end_matches([',', ';', ':', '.']))
+}
+
+/// Decodes numeric references and the named entities WordPress emits.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let name = &rest[1..end];
+            let ch = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => name
+                    .strip_prefix("#x")
+                    .or_else(|| name.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| name.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|ch| (ch, end))
+        });
+        match decoded {
+            Some((ch, end)) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Head tags for a single piece of content. `canonical` and `image` may be
+/// site paths or absolute URLs.
+#[component]
+pub fn PageMeta(
+    #[prop(into)] title: String,
+    #[prop(into)] description: String,
+    #[prop(into)] canonical: String,
+    image: Option<String>,
+    /// RFC 3339; marks the page as an article for OpenGraph.
+    #[prop(optional, into)]
+    published_time: Option<String>,
+) -> impl IntoView {
+    let canonical = absolute_url(&canonical);
+    let image = image.map(|image| absolute_url(&image));
+    let card = if image.is_some() { "summary_large_image" } else { "summary" };
+    let og_type = if published_time.is_some() { "article" } else { "website" };
+
+    view! {
+        <Title text=format!("{} — Jake Wray", title)/>
+        <Meta name="description" content=description.clone()/>
+        <Link rel="canonical" href=canonical.clone()/>
+        <Meta property="og:site_name" content="Jake Wray"/>
+        <Meta property="og:type" content=og_type/>
+        <Meta property="og:title" content=title.clone()/>
+        <Meta property="og:description" content=description.clone()/>
+        <Meta property="og:url" content=canonical/>
+        {image.clone().map(|image| view! { <Meta property="og:image" content=image/> })}
+        {published_time.map(|time| view! { <Meta property="article:published_time" content=time/> })}
+        <Meta name="twitter:card" content=card/>
+        <Meta name="twitter:title" content=title/>
+        <Meta name="twitter:description" content=description/>
+        {image.map(|image| view! { <Meta name="twitter:image" content=image/> })}
+    }
+}
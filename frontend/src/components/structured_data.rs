@@ -0,0 +1,133 @@
+//! schema.org JSON-LD for search engines' rich results, built from the
+//! `shared` content types.
+
+use crate::components::seo::{absolute_url, describe};
+use leptos::prelude::*;
+use serde_json::{json, Value};
+use shared::{Article, BlogPost, CreativeWork, CreativeType};
+
+const NAME: &str = "Jake Wray";
+
+/// The news outlet a syndicated article first ran in.
+#[derive(Clone, Debug)]
+pub struct Outlet {
+    pub name: String,
+    /// The article on the outlet's site.
+    pub url: Option<String>,
+}
+
+/// The site-wide entity everything else points at as author.
+pub fn person() -> Value {
+    json!({
+        "@context": "https://schema.org",
+        "@type": "Person",
+        "@id": absolute_url("/#person"),
+        "name": NAME,
+        "url": absolute_url("/"),
+        "jobTitle": "Journalist",
+        "description": "Journalist, Programmer, Photographer.",
+    })
+}
+
+/// A reference to [`person`], or a separate `Person` for other bylines.
+fn author(name: &str) -> Value {
+    if name == NAME {
+        json!({ "@type": "Person", "@id": absolute_url("/#person"), "name": NAME })
+    } else {
+        json!({ "@type": "Person", "name": name })
+    }
+}
+
+/// `scheme://host` of `url`.
+fn site_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let host = rest.split('/').next().filter(|host| !host.is_empty())?;
+    Some(format!("{}://{}", scheme, host))
+}
+
+/// `NewsArticle` for `article`, published by `outlet` when it was syndicated
+/// and by Jake Wray otherwise.
+pub fn news_article(article: &Article, outlet: Option<&Outlet>) -> Value {
+    let url = absolute_url(&format!("/journalism/{}", article.slug));
+    let mut images: Vec<String> = article.images.iter().map(|image| absolute_url(image)).collect();
+    if images.is_empty() {
+        images.extend(article.cover_image_url.as_deref().map(absolute_url));
+    }
+    let publisher = match outlet {
+        Some(outlet) => {
+            let mut org = json!({ "@type": "NewsMediaOrganization", "name": outlet.name });
+            if let Some(site) = outlet.url.as_deref().and_then(site_of) {
+                org["url"] = json!(site);
+            }
+            org
+        }
+        None => author(NAME),
+    };
+
+    let mut data = json!({
+        "@context": "https://schema.org",
+        "@type": "NewsArticle",
+        "headline": article.title,
+        "url": url,
+        "mainEntityOfPage": url,
+        "datePublished": article.published_at.to_rfc3339(),
+        "author": author(&article.author),
+        "publisher": publisher,
+        "image": images,
+    });
+    if let Some(excerpt) = article.excerpt.as_deref().map(describe).filter(|e| !e.is_empty()) {
+        data["description"] = json!(excerpt);
+    }
+    if let Some(original) = outlet.and_then(|outlet| outlet.url.clone()) {
+        data["isBasedOn"] = json!(original);
+    }
+    data
+}
+
+/// `BlogPosting` for `post`.
+pub fn blog_posting(post: &BlogPost) -> Value {
+    let url = absolute_url(&format!("/blog/{}", post.slug));
+    json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": post.title,
+        "url": url,
+        "mainEntityOfPage": url,
+        "datePublished": post.published_at.to_rfc3339(),
+        "author": author(NAME),
+        "publisher": author(NAME),
+        "keywords": post.tags.clone().unwrap_or_default(),
+    })
+}
+
+/// `CreativeWork` for a story, novel or poem at `path`.
+pub fn creative_work(work: &CreativeWork, path: &str) -> Value {
+    let genre = match work.work_type {
+        CreativeType::Story => "Short story",
+        CreativeType::Novel => "Novel",
+        CreativeType::Poetry => "Poetry",
+    };
+    let mut data = json!({
+        "@context": "https://schema.org",
+        "@type": "CreativeWork",
+        "name": work.title,
+        "url": absolute_url(path),
+        "genre": genre,
+        "author": author(NAME),
+    });
+    if let Some(synopsis) = work.synopsis.as_deref().map(describe).filter(|s| !s.is_empty()) {
+        data["abstract"] = json!(synopsis);
+    }
+    if let Some(published_at) = work.published_at {
+        data["datePublished"] = json!(published_at.to_rfc3339());
+    }
+    data
+}
+
+/// Embeds `data` in the page as a JSON-LD script.
+#[component]
+pub fn JsonLd(data: Value) -> impl IntoView {
+    // `</script>` inside a string would end the element early.
+    let json = data.to_string().replace("</", "<\\/");
+    view! { <script type="application/ld+json" inner_html=json></script> }
+}
@@ -0,0 +1,37 @@
+//! Blog posts, read from `blog_posts` by server functions so a post's page
+//! renders it, and its metadata, during SSR.
+
+use leptos::prelude::*;
+use leptos::server_fn::codec::GetUrl;
+use shared::BlogPost;
+
+/// The published post at `slug`, or `None` when there is none.
+#[server(input = GetUrl)]
+pub async fn get_blog_post(slug: String) -> Result<Option<BlogPost>, ServerFnError> {
+    use sqlx::Row;
+
+    let pool = use_context::<sqlx::PgPool>()
+        .ok_or_else(|| ServerFnError::new("Database unavailable"))?;
+    sqlx::query(
+        r#"
+        SELECT id, slug, title, content, published_at, tags
+        FROM blog_posts
+        WHERE slug = $1 AND published_at <= NOW()
+        "#,
+    )
+    .bind(slug)
+    .map(|row: sqlx::postgres::PgRow| BlogPost {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        content: row.get("content"),
+        published_at: row.get("published_at"),
+        tags: row.get("tags"),
+    })
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to load blog post: {}", e);
+        ServerFnError::new("Database error")
+    })
+}
@@ -1,30 +1,76 @@
-use once_cell::sync::Lazy;
-use serde::{Deserialize, Serialize};
+//! Journalism articles, read from the `articles` table by server functions so
+//! pages render them during SSR and hydrate without fetching them again.
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct JournalismArticle {
-    pub slug: String,
-    pub title: String,
-    pub iso_date: String,
-    pub display_date: String,
-    pub source_url: String,
-    pub content_html: String,
-    pub images: Vec<String>,
-    pub excerpt: String,
+use leptos::prelude::*;
+use leptos::server_fn::codec::GetUrl;
+use shared::Article;
+
+#[cfg(feature = "ssr")]
+fn article_from_row(row: sqlx::postgres::PgRow) -> Article {
+    use sqlx::Row;
+
+    Article {
+        id: row.get("id"),
+        wp_id: row.get("wp_id"),
+        slug: row.get("slug"),
+        title: row.get("title"),
+        subtitle: row.get("subtitle"),
+        excerpt: row.get("excerpt"),
+        content: row.get("content"),
+        cover_image_url: row.get("cover_image_url"),
+        author: row.get("author"),
+        published_at: row.get("published_at"),
+        origin: row
+            .get::<String, _>("origin")
+            .parse()
+            .unwrap_or(shared::Origin::Local),
+        source_url: row.get("source_url"),
+        images: row.get("images"),
+    }
 }
 
-static ARTICLES: Lazy<Vec<JournalismArticle>> = Lazy::new(|| {
-    let mut parsed: Vec<JournalismArticle> = serde_json::from_str(include_str!("journalism.json"))
-        .expect("journalism.json should parse");
+#[cfg(feature = "ssr")]
+fn pool() -> Result<sqlx::PgPool, ServerFnError> {
+    use_context::<sqlx::PgPool>().ok_or_else(|| ServerFnError::new("Database unavailable"))
+}
 
-    parsed.sort_by(|a, b| b.iso_date.cmp(&a.iso_date));
-    parsed
-});
+#[cfg(feature = "ssr")]
+fn db_error(e: sqlx::Error) -> ServerFnError {
+    tracing::error!("Failed to load articles: {}", e);
+    ServerFnError::new("Database error")
+}
 
-pub fn all_articles() -> &'static [JournalismArticle] {
-    &ARTICLES
+/// Every article, newest first.
+#[server(input = GetUrl)]
+pub async fn list_articles() -> Result<Vec<Article>, ServerFnError> {
+    sqlx::query(
+        r#"
+        SELECT id, wp_id, slug, title, subtitle, excerpt, content, cover_image_url, author, published_at, origin,
+               source_url, images
+        FROM articles
+        ORDER BY published_at DESC, id DESC
+        "#,
+    )
+    .map(article_from_row)
+    .fetch_all(&pool()?)
+    .await
+    .map_err(db_error)
 }
 
-pub fn find_article(slug: &str) -> Option<JournalismArticle> {
-    ARTICLES.iter().find(|article| article.slug == slug).cloned()
+/// The article at `slug`, or `None` when there is none.
+#[server(input = GetUrl)]
+pub async fn get_article(slug: String) -> Result<Option<Article>, ServerFnError> {
+    sqlx::query(
+        r#"
+        SELECT id, wp_id, slug, title, subtitle, excerpt, content, cover_image_url, author, published_at, origin,
+               source_url, images
+        FROM articles
+        WHERE slug = $1
+        "#,
+    )
+    .bind(slug)
+    .map(article_from_row)
+    .fetch_optional(&pool()?)
+    .await
+    .map_err(db_error)
 }
@@ -1 +1,2 @@
+pub mod blog;
 pub mod journalism;
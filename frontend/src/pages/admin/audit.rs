@@ -0,0 +1,232 @@
+#[cfg(target_arch = "wasm32")]
+use crate::components::problem;
+#[cfg(target_arch = "wasm32")]
+use gloo_net::http::Request;
+use leptos::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use leptos::task::spawn_local;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone)]
+struct AuditRow {
+    id: i64,
+    occurred_at: String,
+    actor_username: Option<String>,
+    action: String,
+    target_type: Option<String>,
+    target_id: Option<String>,
+    ip: Option<String>,
+    details: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+struct AuditPage {
+    entries: Vec<AuditRow>,
+    next_before: Option<i64>,
+}
+
+/// Fetches one page of entries matching `query`, or the server's error message.
+#[cfg(target_arch = "wasm32")]
+async fn load_page(query: Vec<(&'static str, String)>) -> Result<AuditPage, String> {
+    let resp = Request::get("/admin/audit")
+        .query(query)
+        .send()
+        .await
+        .map_err(|_| "Failed to connect to server".to_string())?;
+    if !resp.ok() {
+        return Err(problem::message(&resp).await);
+    }
+    resp.json()
+        .await
+        .map_err(|_| "Failed to parse response".to_string())
+}
+
+#[component]
+pub fn AdminAudit() -> impl IntoView {
+    let (entries, set_entries) = signal(Vec::<AuditRow>::new());
+    let (next_before, set_next_before) = signal(None::<i64>);
+    let (error, set_error) = signal("".to_string());
+    let (action, set_action) = signal("".to_string());
+    let (target_type, set_target_type) = signal("".to_string());
+    let (target_id, set_target_id) = signal("".to_string());
+    let (since, set_since) = signal("".to_string());
+    let (until, set_until) = signal("".to_string());
+
+    // Filters as query parameters; dates from the pickers cover whole UTC days.
+    #[cfg(target_arch = "wasm32")]
+    let filters = move || {
+        let mut query = Vec::new();
+        for (key, value) in [
+            ("action", action.get_untracked()),
+            ("target_type", target_type.get_untracked()),
+            ("target_id", target_id.get_untracked()),
+        ] {
+            if !value.trim().is_empty() {
+                query.push((key, value.trim().to_string()));
+            }
+        }
+        if !since.get_untracked().is_empty() {
+            query.push(("since", format!("{}T00:00:00Z", since.get_untracked())));
+        }
+        if !until.get_untracked().is_empty() {
+            query.push(("until", format!("{}T23:59:59.999Z", until.get_untracked())));
+        }
+        query
+    };
+
+    // Loads the first page, or the page after the current one when `more`.
+    #[cfg(target_arch = "wasm32")]
+    let load = move |more: bool| {
+        let mut query = filters();
+        if more {
+            match next_before.get_untracked() {
+                Some(before) => query.push(("before", before.to_string())),
+                None => return,
+            }
+        }
+        set_error.set("".to_string());
+        spawn_local(async move {
+            match load_page(query).await {
+                Ok(page) => {
+                    if more {
+                        set_entries.update(|list| list.extend(page.entries));
+                    } else {
+                        set_entries.set(page.entries);
+                    }
+                    set_next_before.set(page.next_before);
+                }
+                Err(msg) => set_error.set(msg),
+            }
+        });
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    Effect::new(move |_| load(false));
+
+    #[cfg(target_arch = "wasm32")]
+    let on_filter = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        load(false);
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_filter = move |_ev: leptos::ev::SubmitEvent| {
+        let _ = (set_entries, set_next_before, set_error);
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let on_more = move |_| load(true);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_more = move |_: leptos::ev::MouseEvent| {};
+
+    let text_filter = move |id: &'static str,
+                            label: &'static str,
+                            placeholder: &'static str,
+                            value: ReadSignal<String>,
+                            set: WriteSignal<String>| {
+        view! {
+            <div class="form-group">
+                <label for=id>{label}</label>
+                <input
+                    id=id
+                    type="text"
+                    placeholder=placeholder
+                    prop:value=move || value.get()
+                    on:input=move |ev| set.set(event_target_value(&ev))
+                />
+            </div>
+        }
+    };
+
+    view! {
+        <div class="container py-12 max-w-5xl">
+            <h1 class="text-3xl mb-8">"Audit Log"</h1>
+
+            <form class="card mb-8 grid grid-cols-1 md:grid-cols-3 gap-4" on:submit=on_filter>
+                {text_filter("audit-action", "Action", "auth. or user.updated", action, set_action)}
+                {text_filter("audit-target-type", "Target type", "user", target_type, set_target_type)}
+                {text_filter("audit-target-id", "Target id", "", target_id, set_target_id)}
+                <div class="form-group">
+                    <label for="audit-since">"From"</label>
+                    <input
+                        id="audit-since"
+                        type="date"
+                        prop:value=move || since.get()
+                        on:input=move |ev| set_since.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="form-group">
+                    <label for="audit-until">"To"</label>
+                    <input
+                        id="audit-until"
+                        type="date"
+                        prop:value=move || until.get()
+                        on:input=move |ev| set_until.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="flex items-end">
+                    <button type="submit" class="btn btn-primary">"Filter"</button>
+                </div>
+            </form>
+
+            {move || {
+                (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 p-3 bg-red-50 border border-red-200 text-red-700 rounded-md text-sm">
+                        {error.get()}
+                    </div>
+                })
+            }}
+
+            <div class="card">
+                <table class="w-full text-sm">
+                    <thead>
+                        <tr>
+                            <th class="text-left">"When"</th>
+                            <th class="text-left">"Who"</th>
+                            <th class="text-left">"Action"</th>
+                            <th class="text-left">"Target"</th>
+                            <th class="text-left">"IP"</th>
+                            <th class="text-left">"Details"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || entries.get()
+                            key=|e| e.id
+                            children=move |e| {
+                                let target = match (e.target_type, e.target_id) {
+                                    (Some(kind), Some(id)) => format!("{} {}", kind, id),
+                                    (Some(kind), None) => kind,
+                                    _ => "".to_string(),
+                                };
+                                let details = match &e.details {
+                                    serde_json::Value::Object(map) if map.is_empty() => "".to_string(),
+                                    other => other.to_string(),
+                                };
+                                view! {
+                                    <tr>
+                                        <td>{e.occurred_at}</td>
+                                        <td>{e.actor_username.unwrap_or_else(|| "—".to_string())}</td>
+                                        <td><code>{e.action}</code></td>
+                                        <td>{target}</td>
+                                        <td>{e.ip.unwrap_or_default()}</td>
+                                        <td><code class="break-all">{details}</code></td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+                {move || entries.with(Vec::is_empty).then(|| view! {
+                    <p class="text-muted mt-4">"No matching entries."</p>
+                })}
+            </div>
+
+            {move || next_before.get().is_some().then(|| view! {
+                <button class="btn btn-secondary mt-4" on:click=on_more>"Load more"</button>
+            })}
+        </div>
+    }
+}
@@ -1,48 +1,29 @@
+use crate::components::csrf::CsrfField;
+use crate::pages::admin::passkey::PasskeyEnroll;
 use leptos::prelude::*;
-#[cfg(target_arch = "wasm32")]
-use leptos_router::hooks::*;
 
 #[component]
 pub fn AdminDashboard() -> impl IntoView {
-    #[cfg(target_arch = "wasm32")]
-    let navigate = use_navigate();
-
-    #[cfg(target_arch = "wasm32")]
-    {
-        // Check if user is authenticated
-        let navigate_clone = navigate.clone();
-        Effect::new(move || {
-            let window = web_sys::window().unwrap();
-            let local_storage = window.local_storage().unwrap().unwrap();
-            let token = local_storage.get_item("admin_token").unwrap_or(None);
-
-            if token.is_none() {
-                navigate_clone("/admin/login", Default::default());
-            }
-        });
-    }
-
-    #[cfg(target_arch = "wasm32")]
-    let logout = move |_| {
-        let window = web_sys::window().unwrap();
-        let local_storage = window.local_storage().unwrap().unwrap();
-        let _ = local_storage.remove_item("admin_token");
-        navigate("/admin/login", Default::default());
-    };
-
-    #[cfg(not(target_arch = "wasm32"))]
-    let logout = move |_| {};
-
+    // Unauthenticated requests never reach this page: the server redirects
+    // `/admin/*` navigations without a valid session to the login page.
     view! {
         <div class="container py-12">
             <div class="flex justify-between items-center mb-8">
                 <h1 class="text-4xl">"Admin Dashboard"</h1>
-                <button 
-                    on:click=logout 
-                    class="btn btn-secondary"
-                >
-                    "Logout"
-                </button>
+                <div class="flex gap-4">
+                    <form method="post" action="/admin/logout/all">
+                        <CsrfField/>
+                        <button type="submit" class="btn btn-secondary">
+                            "Log out all devices"
+                        </button>
+                    </form>
+                    <form method="post" action="/admin/logout">
+                        <CsrfField/>
+                        <button type="submit" class="btn btn-secondary">
+                            "Logout"
+                        </button>
+                    </form>
+                </div>
             </div>
 
             <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6">
@@ -61,10 +42,27 @@ pub fn AdminDashboard() -> impl IntoView {
                     <p class="text-muted">"Upload and manage photos/videos."</p>
                 </a>
 
+                <a href="/admin/users" class="card hover:shadow-md transition">
+                    <h3 class="text-xl font-bold mb-2">"Users"</h3>
+                    <p class="text-muted">"Invite collaborators and manage their roles."</p>
+                </a>
+
+                <a href="/admin/audit" class="card hover:shadow-md transition">
+                    <h3 class="text-xl font-bold mb-2">"Audit Log"</h3>
+                    <p class="text-muted">"Review sign-ins and account changes."</p>
+                </a>
+
+                <a href="/admin/tokens" class="card hover:shadow-md transition">
+                    <h3 class="text-xl font-bold mb-2">"API Tokens"</h3>
+                    <p class="text-muted">"Create scoped tokens for scripts and CI."</p>
+                </a>
+
                 <div class="card">
                     <h3 class="text-xl font-bold mb-2">"Stats"</h3>
                     <p class="text-muted">"Total Articles: [TODO]"</p>
                 </div>
+
+                <PasskeyEnroll/>
             </div>
         </div>
     }
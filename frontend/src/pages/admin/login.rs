@@ -2,6 +2,11 @@
 use gloo_net::http::Request;
 use leptos::prelude::*;
 #[cfg(target_arch = "wasm32")]
+use crate::components::csrf;
+use crate::components::csrf::CsrfField;
+#[cfg(target_arch = "wasm32")]
+use crate::pages::admin::passkey;
+#[cfg(target_arch = "wasm32")]
 use leptos::task::spawn_local;
 use leptos_router::hooks::*;
 use serde::{Deserialize, Serialize};
@@ -10,27 +15,105 @@ use serde::{Deserialize, Serialize};
 struct LoginRequest {
     username: String,
     password: String,
+    session: bool,
+    next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+struct MfaRequest {
+    code: String,
+    mfa_token: Option<String>,
+    session: bool,
+    next: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 struct LoginResponse {
-    token: String,
+    redirect: Option<String>,
+    mfa_token: Option<String>,
+}
+
+/// Posts `body` as JSON and decodes a `LoginResponse`, mapping failures to a
+/// message for the form.
+#[cfg(target_arch = "wasm32")]
+async fn post_login<T: Serialize>(url: &str, body: &T, rejected: &str) -> Result<LoginResponse, String> {
+    let resp = Request::post(url)
+        .header("Content-Type", "application/json")
+        .header(shared::CSRF_HEADER, &csrf::token())
+        .json(body)
+        .map_err(|e| {
+            web_sys::console::log_1(&format!("[Login] Serialize error: {:?}", e).into());
+            "Failed to serialize request".to_string()
+        })?
+        .send()
+        .await
+        .map_err(|e| {
+            web_sys::console::log_1(&format!("[Login] Network error: {:?}", e).into());
+            "Failed to connect to server".to_string()
+        })?;
+
+    web_sys::console::log_1(&format!("[Login] Response status: {}", resp.status()).into());
+
+    if resp.status() == 429 {
+        let retry_after = resp
+            .headers()
+            .get("retry-after")
+            .and_then(|s| s.parse().ok());
+        return Err(locked_message(retry_after));
+    }
+
+    if !resp.ok() {
+        return Err(rejected.to_string());
+    }
+
+    resp.json().await.map_err(|e| {
+        web_sys::console::log_1(&format!("[Login] Parse error: {:?}", e).into());
+        "Failed to parse response".to_string()
+    })
+}
+
+/// Message shown while the server is refusing attempts for this account or
+/// address.
+fn locked_message(retry_after_secs: Option<u64>) -> String {
+    match retry_after_secs {
+        Some(secs) if secs > 60 => format!(
+            "Too many failed attempts. Please try again in {} minutes.",
+            secs.div_ceil(60)
+        ),
+        Some(secs) => format!("Too many failed attempts. Please try again in {} seconds.", secs),
+        None => "Too many failed attempts. Please try again later.".to_string(),
+    }
 }
 
 #[component]
 pub fn AdminLoginPage() -> impl IntoView {
     let (username, set_username) = signal("".to_string());
     let (password, set_password) = signal("".to_string());
+    let (code, set_code) = signal("".to_string());
     let (error, set_error) = signal("".to_string());
     let (loading, set_loading) = signal(false);
+    // Set once the password is accepted for an account with two-factor enabled.
+    let (mfa_token, set_mfa_token) = signal(None::<String>);
 
-    let location = use_location();
+    // `Location` isn't `Copy`, but its query memo is, which keeps these closures `Copy`.
+    let query = use_location().query;
+    let next = move || query.get().get("next");
+    let mfa_step = move || {
+        mfa_token.get().is_some() || query.get().get("step").as_deref() == Some("mfa")
+    };
 
     Effect::new(move || {
-        if let Some(err) = location.query.get().get("error") {
-            if err == "invalid" {
-                set_error.set("Invalid username or password.".to_string());
-            }
+        let query = query.get();
+        if let Some(err) = query.get("error") {
+            let message = match err.as_str() {
+                "invalid" => "Invalid username or password.".to_string(),
+                "invalid_code" => "Invalid verification code.".to_string(),
+                "expired" => "Your sign-in attempt expired. Please log in again.".to_string(),
+                "locked" => locked_message(query.get("retry_after").and_then(|s| s.parse().ok())),
+                _ => return,
+            };
+            set_error.set(message);
         }
     });
 
@@ -45,6 +128,7 @@ pub fn AdminLoginPage() -> impl IntoView {
 
             let username_val = username.get();
             let password_val = password.get();
+            let next_val = next();
             let navigate = navigate.clone();
 
             web_sys::console::log_1(&format!("[Login] Attempting login for user: {}", username_val).into());
@@ -53,59 +137,105 @@ pub fn AdminLoginPage() -> impl IntoView {
                 let req = LoginRequest {
                     username: username_val.clone(),
                     password: password_val.clone(),
+                    session: true,
+                    next: next_val,
                 };
 
                 web_sys::console::log_1(&"[Login] Sending POST /admin/login".into());
 
-                let result = async {
-                    let resp = Request::post("/admin/login")
-                        .header("Content-Type", "application/json")
-                        .json(&req)
-                        .map_err(|e| {
-                            web_sys::console::log_1(&format!("[Login] Serialize error: {:?}", e).into());
-                            "Failed to serialize request".to_string()
-                        })?
-                        .send()
-                        .await
-                        .map_err(|e| {
-                            web_sys::console::log_1(&format!("[Login] Network error: {:?}", e).into());
-                            "Failed to connect to server".to_string()
-                        })?;
-
-                    web_sys::console::log_1(&format!("[Login] Response status: {}", resp.status()).into());
-
-                    if !resp.ok() {
-                        return Err("Invalid username or password".to_string());
+                match post_login("/admin/login", &req, "Invalid username or password").await {
+                    Ok(LoginResponse { mfa_token: Some(token), .. }) => {
+                        web_sys::console::log_1(&"[Login] Password accepted, code required".into());
+                        set_mfa_token.set(Some(token));
+                    }
+                    Ok(data) => {
+                        // The session itself lives in an HttpOnly cookie set by the response.
+                        let target = data.redirect.unwrap_or_else(|| "/admin/dashboard".to_string());
+                        web_sys::console::log_1(&format!("[Login] Success, navigating to {}", target).into());
+                        navigate(&target, Default::default())
                     }
+                    Err(msg) => {
+                        web_sys::console::log_1(&format!("[Login] Error: {}", msg).into());
+                        set_error.set(msg);
+                    }
+                }
+
+                set_loading.set(false);
+            });
+        }
+    };
 
-                    let data: LoginResponse = resp
-                        .json()
-                        .await
-                        .map_err(|e| {
-                            web_sys::console::log_1(&format!("[Login] Parse error: {:?}", e).into());
-                            "Failed to parse response".to_string()
-                        })?;
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_submit = move |_ev: leptos::ev::SubmitEvent| {};
 
-                    web_sys::console::log_1(&"[Login] Token received, storing in localStorage".into());
+    #[cfg(target_arch = "wasm32")]
+    let on_submit_code = {
+        let navigate = use_navigate();
+        move |ev: leptos::ev::SubmitEvent| {
+            ev.prevent_default();
+            set_loading.set(true);
+            set_error.set("".to_string());
 
-                    // Store token in localStorage
-                    let window = web_sys::window().unwrap();
-                    let local_storage = window.local_storage().unwrap().unwrap();
-                    let _ = local_storage.set_item("admin_token", &data.token);
+            let req = MfaRequest {
+                code: code.get(),
+                // Without a token from this page, the server falls back to the
+                // challenge cookie set by a plain form login.
+                mfa_token: mfa_token.get(),
+                session: true,
+                next: next(),
+            };
+            let navigate = navigate.clone();
 
-                    Ok(())
+            spawn_local(async move {
+                web_sys::console::log_1(&"[Login] Sending POST /admin/login/mfa".into());
+
+                match post_login("/admin/login/mfa", &req, "Invalid verification code").await {
+                    Ok(data) => {
+                        let target = data.redirect.unwrap_or_else(|| "/admin/dashboard".to_string());
+                        navigate(&target, Default::default())
+                    }
+                    Err(msg) => set_error.set(msg),
+                }
+
+                set_loading.set(false);
+            });
+        }
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_submit_code = move |_ev: leptos::ev::SubmitEvent| {
+        let _ = (code, set_mfa_token);
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let on_passkey = {
+        let navigate = use_navigate();
+        move |_ev: leptos::ev::MouseEvent| {
+            set_loading.set(true);
+            set_error.set("".to_string());
+            let next_val = next();
+            let navigate = navigate.clone();
+
+            spawn_local(async move {
+                let result = async {
+                    let ceremony = passkey::start("/admin/login/passkey/start").await?;
+                    let credential = passkey::get(&ceremony).await?;
+                    let req = serde_json::json!({
+                        "challenge_id": ceremony.challenge_id,
+                        "credential": credential,
+                        "session": true,
+                        "next": next_val,
+                    });
+                    post_login("/admin/login/passkey/finish", &req, "Passkey not recognised").await
                 }
                 .await;
 
                 match result {
-                    Ok(()) => {
-                        web_sys::console::log_1(&"[Login] Success, navigating to dashboard".into());
-                        navigate("/admin/dashboard", Default::default())
-                    },
-                    Err(msg) => {
-                        web_sys::console::log_1(&format!("[Login] Error: {}", msg).into());
-                        set_error.set(msg);
+                    Ok(data) => {
+                        let target = data.redirect.unwrap_or_else(|| "/admin/dashboard".to_string());
+                        navigate(&target, Default::default())
                     }
+                    Err(msg) => set_error.set(msg),
                 }
 
                 set_loading.set(false);
@@ -114,14 +244,14 @@ pub fn AdminLoginPage() -> impl IntoView {
     };
 
     #[cfg(not(target_arch = "wasm32"))]
-    let on_submit = move |_ev: leptos::ev::SubmitEvent| {};
+    let on_passkey = move |_ev: leptos::ev::MouseEvent| {};
 
     view! {
         <div class="center-page">
             <div class="card form-card">
                 <h1 class="mb-2 text-primary text-center">"Admin Access"</h1>
                 <p class="text-muted text-center mb-6">"Secure dashboard login"</p>
-                
+
                 {move || {
                     (!error.get().is_empty()).then(|| view! {
                         <div class="mb-4 p-3 bg-red-50 border border-red-200 text-red-700 rounded-md text-sm">
@@ -130,7 +260,9 @@ pub fn AdminLoginPage() -> impl IntoView {
                     })
                 }}
 
-                <form autocomplete="on" method="post" action="/admin/login" on:submit=on_submit>
+                <form autocomplete="on" method="post" action="/admin/login" on:submit=on_submit hidden=mfa_step>
+                    <CsrfField/>
+                    <input type="hidden" name="next" value=move || next().unwrap_or_default()/>
                     <div class="form-group">
                         <label for="username">"Username"</label>
                         <input
@@ -143,7 +275,7 @@ pub fn AdminLoginPage() -> impl IntoView {
                             disabled=move || loading.get()
                         />
                     </div>
-                    
+
                     <div class="form-group">
                         <label for="password">"Password"</label>
                         <input
@@ -156,11 +288,43 @@ pub fn AdminLoginPage() -> impl IntoView {
                             disabled=move || loading.get()
                         />
                     </div>
-                    
+
                     <button type="submit" class="btn btn-primary" disabled=move || loading.get()>
                         {move || if loading.get() { "Logging in..." } else { "Login" }}
                     </button>
                 </form>
+
+                <button
+                    type="button"
+                    class="btn btn-secondary mt-4"
+                    on:click=on_passkey
+                    hidden=mfa_step
+                    disabled=move || loading.get()
+                >
+                    "Sign in with a passkey"
+                </button>
+
+                <form autocomplete="off" method="post" action="/admin/login/mfa" on:submit=on_submit_code hidden=move || !mfa_step()>
+                    <CsrfField/>
+                    <input type="hidden" name="next" value=move || next().unwrap_or_default()/>
+                    <div class="form-group">
+                        <label for="code">"Verification code"</label>
+                        <input
+                            id="code"
+                            name="code"
+                            type="text"
+                            inputmode="numeric"
+                            placeholder="6-digit code or recovery code"
+                            autocomplete="one-time-code"
+                            on:input=move |ev| set_code.set(event_target_value(&ev))
+                            disabled=move || loading.get()
+                        />
+                    </div>
+
+                    <button type="submit" class="btn btn-primary" disabled=move || loading.get()>
+                        {move || if loading.get() { "Verifying..." } else { "Verify" }}
+                    </button>
+                </form>
             </div>
         </div>
     }
@@ -1,4 +1,8 @@
+pub mod audit;
 pub mod composer;
 pub mod dashboard;
 pub mod login;
+pub mod passkey;
 pub mod sync_manager;
+pub mod tokens;
+pub mod users;
@@ -0,0 +1,52 @@
+// Bridges the WebAuthn browser API to JSON, with binary fields as base64url
+// strings on both sides, matching `backend/src/auth/webauthn.rs`.
+
+function fromBase64Url(value) {
+  const base64 = value.replace(/-/g, "+").replace(/_/g, "/");
+  const padded = base64.padEnd(Math.ceil(base64.length / 4) * 4, "=");
+  return Uint8Array.from(atob(padded), (c) => c.charCodeAt(0));
+}
+
+function toBase64Url(buffer) {
+  let binary = "";
+  for (const byte of new Uint8Array(buffer)) {
+    binary += String.fromCharCode(byte);
+  }
+  return btoa(binary).replace(/\+/g, "-").replace(/\//g, "_").replace(/=+$/, "");
+}
+
+export async function createPasskey(optionsJson) {
+  const options = JSON.parse(optionsJson);
+  options.challenge = fromBase64Url(options.challenge);
+  options.user.id = fromBase64Url(options.user.id);
+  options.excludeCredentials = (options.excludeCredentials || []).map((c) => ({
+    ...c,
+    id: fromBase64Url(c.id),
+  }));
+
+  const credential = await navigator.credentials.create({ publicKey: options });
+  return JSON.stringify({
+    id: credential.id,
+    client_data_json: toBase64Url(credential.response.clientDataJSON),
+    attestation_object: toBase64Url(credential.response.attestationObject),
+  });
+}
+
+export async function getPasskey(optionsJson) {
+  const options = JSON.parse(optionsJson);
+  options.challenge = fromBase64Url(options.challenge);
+  options.allowCredentials = (options.allowCredentials || []).map((c) => ({
+    ...c,
+    id: fromBase64Url(c.id),
+  }));
+
+  const credential = await navigator.credentials.get({ publicKey: options });
+  const response = credential.response;
+  return JSON.stringify({
+    id: credential.id,
+    client_data_json: toBase64Url(response.clientDataJSON),
+    authenticator_data: toBase64Url(response.authenticatorData),
+    signature: toBase64Url(response.signature),
+    user_handle: response.userHandle ? toBase64Url(response.userHandle) : null,
+  });
+}
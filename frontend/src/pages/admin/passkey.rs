@@ -0,0 +1,124 @@
+#[cfg(target_arch = "wasm32")]
+use crate::components::csrf;
+#[cfg(target_arch = "wasm32")]
+use gloo_net::http::Request;
+use leptos::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use leptos::task::spawn_local;
+use serde::Deserialize;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(module = "/src/pages/admin/passkey.js")]
+extern "C" {
+    #[wasm_bindgen(catch, js_name = createPasskey)]
+    async fn create_passkey(options: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch, js_name = getPasskey)]
+    async fn get_passkey(options: String) -> Result<JsValue, JsValue>;
+}
+
+/// Options from one of the `.../start` endpoints.
+#[derive(Deserialize, Clone)]
+pub struct Ceremony {
+    pub challenge_id: String,
+    pub public_key: serde_json::Value,
+}
+
+#[cfg(target_arch = "wasm32")]
+pub async fn start(url: &str) -> Result<Ceremony, String> {
+    let resp = Request::post(url)
+        .header(shared::CSRF_HEADER, &csrf::token())
+        .send()
+        .await
+        .map_err(|_| "Failed to connect to server".to_string())?;
+    if !resp.ok() {
+        return Err("Passkeys are unavailable right now".to_string());
+    }
+    resp.json()
+        .await
+        .map_err(|_| "Failed to parse response".to_string())
+}
+
+/// Asks the browser for a new passkey; resolves to the credential JSON.
+#[cfg(target_arch = "wasm32")]
+pub async fn create(ceremony: &Ceremony) -> Result<serde_json::Value, String> {
+    let credential = create_passkey(ceremony.public_key.to_string())
+        .await
+        .map_err(|e| {
+            web_sys::console::log_1(&e);
+            "Passkey creation was cancelled".to_string()
+        })?;
+    serde_json::from_str(&credential.as_string().unwrap_or_default())
+        .map_err(|_| "Unexpected passkey response".to_string())
+}
+
+/// Asks the browser to sign the challenge with a saved passkey.
+#[cfg(target_arch = "wasm32")]
+pub async fn get(ceremony: &Ceremony) -> Result<serde_json::Value, String> {
+    let credential = get_passkey(ceremony.public_key.to_string())
+        .await
+        .map_err(|e| {
+            web_sys::console::log_1(&e);
+            "Passkey sign-in was cancelled".to_string()
+        })?;
+    serde_json::from_str(&credential.as_string().unwrap_or_default())
+        .map_err(|_| "Unexpected passkey response".to_string())
+}
+
+/// Registers a passkey for the signed-in user.
+#[component]
+pub fn PasskeyEnroll() -> impl IntoView {
+    let (status, set_status) = signal("".to_string());
+    let (busy, set_busy) = signal(false);
+
+    #[cfg(target_arch = "wasm32")]
+    let on_click = move |_| {
+        set_busy.set(true);
+        set_status.set("".to_string());
+        spawn_local(async move {
+            let result: Result<(), String> = async {
+                let ceremony = start("/admin/passkeys/register/start").await?;
+                let credential = create(&ceremony).await?;
+                let resp = Request::post("/admin/passkeys/register/finish")
+                    .header(shared::CSRF_HEADER, &csrf::token())
+                    .json(&serde_json::json!({
+                        "challenge_id": ceremony.challenge_id,
+                        "credential": credential,
+                    }))
+                    .map_err(|_| "Failed to serialize request".to_string())?
+                    .send()
+                    .await
+                    .map_err(|_| "Failed to connect to server".to_string())?;
+                if !resp.ok() {
+                    return Err("The passkey was not accepted".to_string());
+                }
+                Ok(())
+            }
+            .await;
+
+            set_status.set(match result {
+                Ok(()) => "Passkey added. You can now sign in with it.".to_string(),
+                Err(msg) => msg,
+            });
+            set_busy.set(false);
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_click = move |_: leptos::ev::MouseEvent| {
+        let _ = (set_status, set_busy);
+    };
+
+    view! {
+        <div class="card">
+            <h3 class="text-xl font-bold mb-2">"Passkeys"</h3>
+            <p class="text-muted mb-4">"Sign in with your device instead of a password."</p>
+            <button class="btn btn-secondary" on:click=on_click disabled=move || busy.get()>
+                {move || if busy.get() { "Waiting for device..." } else { "Add a passkey" }}
+            </button>
+            <p class="text-muted text-sm mt-2">{move || status.get()}</p>
+        </div>
+    }
+}
@@ -0,0 +1,268 @@
+#[cfg(target_arch = "wasm32")]
+use crate::components::{csrf, problem};
+#[cfg(target_arch = "wasm32")]
+use gloo_net::http::Request;
+use leptos::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use leptos::task::spawn_local;
+use serde::Deserialize;
+use shared::{Permission, Role};
+
+#[derive(Deserialize, Clone)]
+struct TokenRow {
+    id: String,
+    name: String,
+    scopes: Vec<String>,
+    created_at: String,
+    last_used_at: Option<String>,
+    expires_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+struct Me {
+    role: Role,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+struct CreatedToken {
+    token: String,
+}
+
+/// GETs `url` as JSON, or the server's error message.
+#[cfg(target_arch = "wasm32")]
+async fn get_json<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, String> {
+    let resp = Request::get(url)
+        .send()
+        .await
+        .map_err(|_| "Failed to connect to server".to_string())?;
+    if !resp.ok() {
+        return Err(problem::message(&resp).await);
+    }
+    resp.json()
+        .await
+        .map_err(|_| "Failed to parse response".to_string())
+}
+
+#[component]
+pub fn AdminTokens() -> impl IntoView {
+    let (tokens, set_tokens) = signal(Vec::<TokenRow>::new());
+    let (role, set_role) = signal(None::<Role>);
+    let (error, set_error) = signal("".to_string());
+    let (name, set_name) = signal("".to_string());
+    let (scopes, set_scopes) = signal(Vec::<Permission>::new());
+    let (expires_in_days, set_expires_in_days) = signal("90".to_string());
+    let (created, set_created) = signal(None::<String>);
+
+    #[cfg(target_arch = "wasm32")]
+    let reload = move || {
+        spawn_local(async move {
+            match get_json("/admin/tokens").await {
+                Ok(list) => set_tokens.set(list),
+                Err(msg) => set_error.set(msg),
+            }
+        });
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    Effect::new(move |_| {
+        reload();
+        spawn_local(async move {
+            if let Ok(me) = get_json::<Me>("/admin/me").await {
+                set_role.set(Some(me.role));
+            }
+        });
+    });
+
+    #[cfg(target_arch = "wasm32")]
+    let on_create = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_error.set("".to_string());
+        set_created.set(None);
+        let body = serde_json::json!({
+            "name": name.get(),
+            "scopes": scopes.get().iter().map(|p| p.scope()).collect::<Vec<_>>(),
+            "expires_in_days": expires_in_days.get().parse::<i64>().ok(),
+        });
+        spawn_local(async move {
+            let resp = match Request::post("/admin/tokens")
+                .header(shared::CSRF_HEADER, &csrf::token())
+                .json(&body)
+            {
+                Ok(req) => req.send().await,
+                Err(_) => return set_error.set("Failed to serialize request".to_string()),
+            };
+            let resp = match resp {
+                Ok(resp) => resp,
+                Err(_) => return set_error.set("Failed to connect to server".to_string()),
+            };
+            if !resp.ok() {
+                return set_error.set(problem::message(&resp).await);
+            }
+            match resp.json::<CreatedToken>().await {
+                Ok(created) => {
+                    set_created.set(Some(created.token));
+                    set_name.set("".to_string());
+                    set_scopes.set(Vec::new());
+                    reload();
+                }
+                Err(_) => set_error.set("Failed to parse response".to_string()),
+            }
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_create = move |_ev: leptos::ev::SubmitEvent| {
+        let _ = (set_tokens, set_role, set_error, set_created, expires_in_days);
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let revoke = move |id: String| {
+        if !window()
+            .confirm_with_message("Revoke this token? Scripts using it will stop working.")
+            .unwrap_or(false)
+        {
+            return;
+        }
+        set_error.set("".to_string());
+        spawn_local(async move {
+            let resp = Request::delete(&format!("/admin/tokens/{}", id))
+                .header(shared::CSRF_HEADER, &csrf::token())
+                .send()
+                .await;
+            match resp {
+                Ok(resp) if !resp.ok() => set_error.set(problem::message(&resp).await),
+                Ok(_) => {}
+                Err(_) => set_error.set("Failed to connect to server".to_string()),
+            }
+            reload();
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let revoke = move |_id: String| {};
+
+    // Only offer scopes the user's role could actually use.
+    let scope_choices = move || {
+        let role = role.get()?;
+        let choices = Permission::ALL
+            .into_iter()
+            .filter(|p| role.allows(*p))
+            .map(|p| {
+                view! {
+                    <label class="flex items-center gap-2">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || scopes.with(|s| s.contains(&p))
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                set_scopes.update(|s| {
+                                    s.retain(|x| *x != p);
+                                    if checked {
+                                        s.push(p);
+                                    }
+                                });
+                            }
+                        />
+                        <code>{p.scope()}</code>
+                    </label>
+                }
+            })
+            .collect_view();
+        Some(choices)
+    };
+
+    view! {
+        <div class="container py-12 max-w-3xl">
+            <h1 class="text-3xl mb-8">"API Tokens"</h1>
+
+            {move || {
+                (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 p-3 bg-red-50 border border-red-200 text-red-700 rounded-md text-sm">
+                        {error.get()}
+                    </div>
+                })
+            }}
+
+            {move || created.get().map(|token| view! {
+                <div class="mb-4 p-3 bg-green-50 border border-green-200 rounded-md text-sm">
+                    <p class="mb-2">"Copy this token now. It won't be shown again."</p>
+                    <code class="break-all">{token}</code>
+                </div>
+            })}
+
+            <div class="card mb-8">
+                <table class="w-full text-sm">
+                    <thead>
+                        <tr>
+                            <th class="text-left">"Name"</th>
+                            <th class="text-left">"Scopes"</th>
+                            <th class="text-left">"Created"</th>
+                            <th class="text-left">"Last used"</th>
+                            <th class="text-left">"Expires"</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || tokens.get()
+                            key=|t| t.id.clone()
+                            children=move |t| {
+                                let id = t.id.clone();
+                                view! {
+                                    <tr>
+                                        <td>{t.name}</td>
+                                        <td><code>{t.scopes.join(" ")}</code></td>
+                                        <td>{t.created_at}</td>
+                                        <td>{t.last_used_at.unwrap_or_else(|| "Never".to_string())}</td>
+                                        <td>{t.expires_at.unwrap_or_else(|| "Never".to_string())}</td>
+                                        <td class="text-right">
+                                            <button
+                                                class="btn btn-secondary"
+                                                on:click=move |_| revoke(id.clone())
+                                            >
+                                                "Revoke"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </div>
+
+            <form class="card" on:submit=on_create>
+                <h3 class="text-xl font-bold mb-4">"Create a token"</h3>
+                <div class="form-group">
+                    <label for="token-name">"Name"</label>
+                    <input
+                        id="token-name"
+                        type="text"
+                        placeholder="CI deploy hook"
+                        prop:value=move || name.get()
+                        on:input=move |ev| set_name.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="form-group">
+                    <label>"Scopes"</label>
+                    <div class="grid grid-cols-2 gap-2">{scope_choices}</div>
+                </div>
+                <div class="form-group">
+                    <label for="token-expiry">"Expires"</label>
+                    <select
+                        id="token-expiry"
+                        on:change=move |ev| set_expires_in_days.set(event_target_value(&ev))
+                    >
+                        <option value="30">"In 30 days"</option>
+                        <option value="90" selected>"In 90 days"</option>
+                        <option value="365">"In a year"</option>
+                        <option value="">"Never"</option>
+                    </select>
+                </div>
+                <button type="submit" class="btn btn-primary">"Create token"</button>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,279 @@
+#[cfg(target_arch = "wasm32")]
+use crate::components::{csrf, problem};
+#[cfg(target_arch = "wasm32")]
+use gloo_net::http::Request;
+use leptos::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use leptos::task::spawn_local;
+use serde::Deserialize;
+use shared::Role;
+
+#[derive(Deserialize, Clone)]
+struct UserRow {
+    id: String,
+    username: String,
+    role: Role,
+    two_factor: bool,
+    disabled: bool,
+}
+
+/// Fetches the user list, or the server's error message.
+#[cfg(target_arch = "wasm32")]
+async fn load_users() -> Result<Vec<UserRow>, String> {
+    let resp = Request::get("/admin/users")
+        .send()
+        .await
+        .map_err(|_| "Failed to connect to server".to_string())?;
+    if !resp.ok() {
+        return Err(problem::message(&resp).await);
+    }
+    resp.json()
+        .await
+        .map_err(|_| "Failed to parse response".to_string())
+}
+
+/// Turns a non-2xx response into its error message.
+#[cfg(target_arch = "wasm32")]
+async fn check(resp: Result<gloo_net::http::Response, gloo_net::Error>) -> Result<(), String> {
+    let resp = resp.map_err(|_| "Failed to connect to server".to_string())?;
+    if resp.ok() {
+        Ok(())
+    } else {
+        Err(problem::message(&resp).await)
+    }
+}
+
+#[component]
+pub fn AdminUsers() -> impl IntoView {
+    let (users, set_users) = signal(Vec::<UserRow>::new());
+    let (error, set_error) = signal("".to_string());
+    let (username, set_username) = signal("".to_string());
+    let (password, set_password) = signal("".to_string());
+    let (role, set_role) = signal(Role::Contributor);
+
+    #[cfg(target_arch = "wasm32")]
+    let reload = move || {
+        spawn_local(async move {
+            match load_users().await {
+                Ok(list) => set_users.set(list),
+                Err(msg) => set_error.set(msg),
+            }
+        });
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    Effect::new(move |_| reload());
+
+    #[cfg(target_arch = "wasm32")]
+    let on_create = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_error.set("".to_string());
+        let body = serde_json::json!({
+            "username": username.get(),
+            "password": password.get(),
+            "role": role.get(),
+        });
+        spawn_local(async move {
+            let resp = match Request::post("/admin/users")
+                .header(shared::CSRF_HEADER, &csrf::token())
+                .json(&body)
+            {
+                Ok(req) => req.send().await,
+                Err(_) => return set_error.set("Failed to serialize request".to_string()),
+            };
+            match check(resp).await {
+                Ok(()) => {
+                    set_username.set("".to_string());
+                    set_password.set("".to_string());
+                    reload();
+                }
+                Err(msg) => set_error.set(msg),
+            }
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let on_create = move |_ev: leptos::ev::SubmitEvent| {
+        let _ = (set_users, set_error, role);
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    let change_role = move |id: String, role: Role| {
+        set_error.set("".to_string());
+        spawn_local(async move {
+            let resp = match Request::patch(&format!("/admin/users/{}", id))
+                .header(shared::CSRF_HEADER, &csrf::token())
+                .json(&serde_json::json!({ "role": role }))
+            {
+                Ok(req) => req.send().await,
+                Err(_) => return set_error.set("Failed to serialize request".to_string()),
+            };
+            if let Err(msg) = check(resp).await {
+                set_error.set(msg);
+            }
+            reload();
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let change_role = move |_id: String, _role: Role| {};
+
+    #[cfg(target_arch = "wasm32")]
+    let set_disabled = move |id: String, disabled: bool| {
+        set_error.set("".to_string());
+        spawn_local(async move {
+            let resp = match Request::patch(&format!("/admin/users/{}", id))
+                .header(shared::CSRF_HEADER, &csrf::token())
+                .json(&serde_json::json!({ "disabled": disabled }))
+            {
+                Ok(req) => req.send().await,
+                Err(_) => return set_error.set("Failed to serialize request".to_string()),
+            };
+            if let Err(msg) = check(resp).await {
+                set_error.set(msg);
+            }
+            reload();
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let set_disabled = move |_id: String, _disabled: bool| {};
+
+    #[cfg(target_arch = "wasm32")]
+    let delete_user = move |id: String| {
+        if !window().confirm_with_message("Delete this user?").unwrap_or(false) {
+            return;
+        }
+        set_error.set("".to_string());
+        spawn_local(async move {
+            let resp = Request::delete(&format!("/admin/users/{}", id))
+                .header(shared::CSRF_HEADER, &csrf::token())
+                .send()
+                .await;
+            if let Err(msg) = check(resp).await {
+                set_error.set(msg);
+            }
+            reload();
+        });
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let delete_user = move |_id: String| {};
+
+    let role_options = move |selected: Role| {
+        Role::ALL
+            .into_iter()
+            .map(|r| {
+                view! { <option value=r.as_str() selected={r == selected}>{r.as_str()}</option> }
+            })
+            .collect_view()
+    };
+
+    view! {
+        <div class="container py-12 max-w-3xl">
+            <h1 class="text-3xl mb-8">"Users"</h1>
+
+            {move || {
+                (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 p-3 bg-red-50 border border-red-200 text-red-700 rounded-md text-sm">
+                        {error.get()}
+                    </div>
+                })
+            }}
+
+            <div class="card mb-8">
+                <table class="w-full">
+                    <thead>
+                        <tr>
+                            <th class="text-left">"Username"</th>
+                            <th class="text-left">"Role"</th>
+                            <th class="text-left">"2FA"</th>
+                            <th class="text-left">"Status"</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || users.get()
+                            key=|u| (u.id.clone(), u.role, u.disabled)
+                            children=move |u| {
+                                let id_for_role = u.id.clone();
+                                let id_for_status = u.id.clone();
+                                let id_for_delete = u.id.clone();
+                                let disabled = u.disabled;
+                                view! {
+                                    <tr>
+                                        <td>{u.username.clone()}</td>
+                                        <td>
+                                            <select on:change=move |ev| {
+                                                if let Ok(role) = event_target_value(&ev).parse() {
+                                                    change_role(id_for_role.clone(), role);
+                                                }
+                                            }>
+                                                {role_options(u.role)}
+                                            </select>
+                                        </td>
+                                        <td>{if u.two_factor { "On" } else { "Off" }}</td>
+                                        <td>{if disabled { "Disabled" } else { "Active" }}</td>
+                                        <td class="text-right">
+                                            <button
+                                                class="btn btn-secondary"
+                                                on:click=move |_| set_disabled(id_for_status.clone(), !disabled)
+                                            >
+                                                {if disabled { "Enable" } else { "Disable" }}
+                                            </button>
+                                            <button
+                                                class="btn btn-secondary"
+                                                on:click=move |_| delete_user(id_for_delete.clone())
+                                            >
+                                                "Delete"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </div>
+
+            <form class="card" on:submit=on_create>
+                <h3 class="text-xl font-bold mb-4">"Add a user"</h3>
+                <div class="form-group">
+                    <label for="new-username">"Username"</label>
+                    <input
+                        id="new-username"
+                        type="text"
+                        autocomplete="off"
+                        prop:value=move || username.get()
+                        on:input=move |ev| set_username.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="form-group">
+                    <label for="new-password">"Initial password"</label>
+                    <input
+                        id="new-password"
+                        type="password"
+                        autocomplete="new-password"
+                        prop:value=move || password.get()
+                        on:input=move |ev| set_password.set(event_target_value(&ev))
+                    />
+                </div>
+                <div class="form-group">
+                    <label for="new-role">"Role"</label>
+                    <select
+                        id="new-role"
+                        on:change=move |ev| {
+                            if let Ok(r) = event_target_value(&ev).parse() {
+                                set_role.set(r);
+                            }
+                        }
+                    >
+                        {role_options(Role::Contributor)}
+                    </select>
+                </div>
+                <button type="submit" class="btn btn-primary">"Create user"</button>
+            </form>
+        </div>
+    }
+}
@@ -1,4 +1,5 @@
 pub mod about;
 pub mod admin;
 pub mod contact;
+pub mod search;
 pub mod sections;
@@ -0,0 +1,96 @@
+#[cfg(target_arch = "wasm32")]
+use crate::pages::sections::fetch_json;
+#[cfg(target_arch = "wasm32")]
+use gloo_net::http::Request;
+use leptos::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_query_map;
+use shared::{SearchKind, SearchResult};
+
+fn kind_label(kind: SearchKind) -> &'static str {
+    match kind {
+        SearchKind::Article => "Journalism",
+        SearchKind::BlogPost => "Blog",
+        SearchKind::CreativeWork => "Writing",
+    }
+}
+
+#[component]
+fn SearchHit(result: SearchResult) -> impl IntoView {
+    let mut label = kind_label(result.kind).to_string();
+    if let Some(at) = result.published_at {
+        label.push_str(&at.format(" · %B %-d, %Y").to_string());
+    }
+    let title = match result.url() {
+        Some(href) => view! { <a href=href>{result.title}</a> }.into_any(),
+        None => view! { <span>{result.title}</span> }.into_any(),
+    };
+    view! {
+        <article class="card mb-6">
+            <p class="journalism-date">{label}</p>
+            <h2 class="text-2xl font-bold mb-2">{title}</h2>
+            <p class="search-snippet" inner_html=result.snippet></p>
+        </article>
+    }
+}
+
+#[component]
+pub fn SearchPage() -> impl IntoView {
+    let query = use_query_map();
+    let q = move || query.with(|q| q.get("q")).map(|q| q.trim().to_string()).unwrap_or_default();
+    // `None` while loading.
+    let (results, set_results) = signal(None::<Vec<SearchResult>>);
+    let (error, set_error) = signal("".to_string());
+
+    #[cfg(target_arch = "wasm32")]
+    Effect::new(move |_| {
+        let q = q();
+        set_error.set("".to_string());
+        if q.is_empty() {
+            set_results.set(Some(Vec::new()));
+            return;
+        }
+        set_results.set(None);
+        spawn_local(async move {
+            let request = Request::get("/api/search").query([("q", q)]);
+            match fetch_json::<Vec<SearchResult>>(request).await {
+                Ok(found) => set_results.set(Some(found.unwrap_or_default())),
+                Err(msg) => set_error.set(msg),
+            }
+        });
+    });
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (set_results, set_error);
+
+    view! {
+        <div class="container py-12 max-w-3xl">
+            <h1 class="text-4xl mb-6">"Search"</h1>
+
+            {move || {
+                (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 p-3 bg-red-50 border border-red-200 text-red-700 rounded-md text-sm">
+                        {error.get()}
+                    </div>
+                })
+            }}
+
+            {move || match results.get() {
+                None if !error.get().is_empty() => ().into_any(),
+                None => view! { <p class="text-muted">"Searching…"</p> }.into_any(),
+                Some(_) if q().is_empty() => view! {
+                    <p class="text-muted">"Search articles, blog posts and writing."</p>
+                }.into_any(),
+                Some(list) if list.is_empty() => view! {
+                    <p class="text-muted">{format!("Nothing matches \u{201c}{}\u{201d}.", q())}</p>
+                }.into_any(),
+                Some(list) => list
+                    .into_iter()
+                    .map(|result| view! { <SearchHit result/> })
+                    .collect_view()
+                    .into_any(),
+            }}
+        </div>
+    }
+}
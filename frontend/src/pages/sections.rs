@@ -1,6 +1,14 @@
-use crate::data::journalism;
+use crate::components::markdown::{first_image, to_html, Markdown};
+use crate::components::seo::{describe, PageMeta};
+use crate::components::structured_data::{blog_posting, news_article, JsonLd, Outlet};
+use crate::data::{blog, journalism};
+#[cfg(target_arch = "wasm32")]
+use gloo_net::http::Request;
 use leptos::prelude::*;
-use leptos_router::hooks::use_params_map;
+#[cfg(target_arch = "wasm32")]
+use leptos::task::spawn_local;
+use leptos_router::hooks::{use_params_map, use_query_map};
+use shared::{Article, BlogPost};
 
 fn strip_tags(s: &str) -> String {
     let mut out = String::with_capacity(s.len());
@@ -214,9 +222,56 @@ fn italicize_origin_line(html: &str) -> String {
     out
 }
 
+/// The outlet named in an article's "originally appeared in" line, the one
+/// `italicize_origin_line` styles, along with its link to the original.
+fn original_outlet(html: &str) -> Option<Outlet> {
+    const PHRASE: &str = "originally appeared in";
+    let start = html.to_ascii_lowercase().find(PHRASE)? + PHRASE.len();
+    let rest = &html[start..];
+    let line = &rest[..rest.find("</p>").unwrap_or(rest.len())];
+    let url = extract_between(line, "href=\"", "\"", 0).map(|(url, _)| url);
+    let name = describe(line);
+    let name = name.trim().trim_end_matches('.').trim();
+    let name = name.strip_prefix("the ").unwrap_or(name);
+    (!name.is_empty()).then(|| Outlet {
+        name: name.to_string(),
+        url,
+    })
+}
+
+/// `article`'s date as printed in the piece, falling back to its publication date.
+fn article_date(article: &Article) -> String {
+    extract_printed_date(&article.content)
+        .unwrap_or_else(|| article.published_at.format("%B %-d, %Y").to_string())
+}
+
+#[component]
+fn JournalismCard(article: Article) -> impl IntoView {
+    let preview_text = extract_body_preview(&article.content)
+        .or_else(|| article.excerpt.clone())
+        .unwrap_or_default();
+    let date = article_date(&article);
+    let image = article.cover_image_url;
+    let thumb_src = image.clone().unwrap_or_else(|| "data:image/svg+xml;utf8,<svg xmlns='http://www.w3.org/2000/svg' width='400' height='300' viewBox='0 0 400 300'><rect width='400' height='300' fill='%23e5e7eb'/><text x='50%' y='50%' dominant-baseline='middle' text-anchor='middle' fill='%239ca3af' font-size='16' font-family='Inter, sans-serif'>Image coming soon</text></svg>".to_string());
+    view! {
+        <a href=format!("/journalism/{}", article.slug) class="journalism-card">
+            <div class="journalism-thumb">
+                <img src=thumb_src class="journalism-img" alt="article thumbnail"/>
+                {image.is_none().then(|| view! { <div class="journalism-placeholder-text">"Image coming soon"</div> })}
+            </div>
+            <div class="journalism-body">
+                <p class="journalism-date">{date}</p>
+                <h3 class="journalism-title">{article.title}</h3>
+                <p class="journalism-excerpt">{preview_text}</p>
+                <div class="journalism-link">"Read more →"</div>
+            </div>
+        </a>
+    }
+}
+
 #[component]
 pub fn JournalismPage() -> impl IntoView {
-    let articles = journalism::all_articles();
+    let articles = Resource::new(|| (), |_| journalism::list_articles());
 
     view! {
         <div class="container py-12">
@@ -225,81 +280,96 @@ pub fn JournalismPage() -> impl IntoView {
                 "Reporting on northern communities, Indigenous culture, and public interest stories."
             </p>
 
-            <div class="journalism-grid">
-                {articles
-                    .iter()
-                    .map(|article| {
-                        let slug = article.slug.clone();
-                        let title = article.title.clone();
-                        let preview_text = extract_body_preview(&article.content_html)
-                            .unwrap_or_else(|| article.excerpt.clone());
-                        let image = article.images.get(0).cloned();
-                        let thumb_src = image.clone().unwrap_or_else(|| "data:image/svg+xml;utf8,<svg xmlns='http://www.w3.org/2000/svg' width='400' height='300' viewBox='0 0 400 300'><rect width='400' height='300' fill='%23e5e7eb'/><text x='50%' y='50%' dominant-baseline='middle' text-anchor='middle' fill='%239ca3af' font-size='16' font-family='Inter, sans-serif'>Image coming soon</text></svg>".to_string());
-                        let date = extract_printed_date(&article.content_html)
-                            .unwrap_or_else(|| article.display_date.clone());
-                        view! {
-                            <a href=format!("/journalism/{}", slug) class="journalism-card">
-                                <div class="journalism-thumb">
-                                    <img src=thumb_src class="journalism-img" alt="article thumbnail"/>
-                                    {image.is_none().then(|| view! { <div class="journalism-placeholder-text">"Image coming soon"</div> })}
-                                </div>
-                                <div class="journalism-body">
-                                    <p class="journalism-date">{date}</p>
-                                    <h3 class="journalism-title">{title}</h3>
-                                    <p class="journalism-excerpt">{preview_text}</p>
-                                    <div class="journalism-link">"Read more →"</div>
-                                </div>
-                            </a>
+            <Suspense fallback=|| view! { <p class="text-muted">"Loading…"</p> }>
+                {move || Suspend::new(async move {
+                    match articles.await {
+                        Ok(list) if list.is_empty() => view! { <p class="text-muted">"No articles yet."</p> }.into_any(),
+                        Ok(list) => view! {
+                            <div class="journalism-grid">
+                                {list
+                                    .into_iter()
+                                    .map(|article| view! { <JournalismCard article/> })
+                                    .collect_view()}
+                            </div>
                         }
-                    })
-                    .collect_view()}
-            </div>
+                        .into_any(),
+                        Err(_) => view! { <p class="text-muted">"Articles could not be loaded."</p> }.into_any(),
+                    }
+                })}
+            </Suspense>
         </div>
     }
 }
 
+/// Tidies an article's WordPress HTML for display: drops the subhead, restyles
+/// the printed date and byline, and links images to their full-size files.
+fn article_body(article: &Article, display_date: &str) -> String {
+    let content_html = &article.content;
+    let s = if let Some((_, end)) = extract_between(content_html, "<h4", "</h4>", 0) {
+        let start = content_html.find("<h4").unwrap_or(0);
+        let mut s = content_html.clone();
+        s.replace_range(start..end, "");
+        s
+    } else {
+        content_html.clone()
+    };
+    let s = replace_date_paragraph(&s, display_date);
+    let s = bold_byline(&s);
+    let s = bold_byline(&s);
+    let s = italicize_origin_line(&s);
+    linkify_images(&s)
+}
+
 #[component]
 pub fn JournalismArticlePage() -> impl IntoView {
     let params = use_params_map();
-    let slug = move || params.with(|p| p.get("slug").map(|s| s.to_string()).unwrap_or_default());
-    let article = move || journalism::find_article(&slug());
+    let slug = move || params.with(|p| p.get("slug").unwrap_or_default());
+    let article = Resource::new(slug, journalism::get_article);
 
     view! {
         <div class="container py-12 max-w-4xl">
-            {move || {
-                article()
-                    .map(|article| {
-                        let display_date = extract_printed_date(&article.content_html)
-                            .unwrap_or_else(|| article.display_date.clone());
-                        let title = article.title.clone();
-                        let source_url = article.source_url.clone();
-                        let images = article.images.clone();
-                        let content_html = article.content_html.clone();
-                        // remove first subhead h4 from article content
-                        let content_html = {
-
-                            let s = if let Some((_, end)) = extract_between(&content_html, "<h4", "</h4>", 0) {
-                                let start = content_html.find("<h4").unwrap_or(0);
-                                let mut s = content_html.clone();
-                                s.replace_range(start..end, "");
-                                s
-                            } else { content_html };
-                             let s = replace_date_paragraph(&s, &display_date);
-                             let s = bold_byline(&s);
-                             let s = bold_byline(&s);
-                             let s = italicize_origin_line(&s);
-                             linkify_images(&s)
-                        };
-                        view! {
-                            <div class="article-container">
-                                <h1 class="mb-4 text-4xl font-bold text-gray-900">{title}</h1>
-                                <div class="article-content prose" inner_html=content_html></div>
-                            </div>
+            <Suspense fallback=|| view! { <p class="text-muted">"Loading…"</p> }>
+                {move || Suspend::new(async move {
+                    match article.await {
+                        Ok(Some(article)) => {
+                            let display_date = article_date(&article);
+                            let content_html = article_body(&article, &display_date);
+                            let description = article
+                                .excerpt
+                                .as_deref()
+                                .map(describe)
+                                .filter(|d| !d.is_empty())
+                                .or_else(|| extract_body_preview(&article.content).map(|p| describe(&p)))
+                                .unwrap_or_else(|| article.title.clone());
+                            // Pieces that ran elsewhere first point search engines at the
+                            // original outlet, not the old portfolio `source_url` copy.
+                            let outlet = original_outlet(&article.content);
+                            let canonical = outlet
+                                .as_ref()
+                                .and_then(|o| o.url.clone())
+                                .unwrap_or_else(|| format!("/journalism/{}", article.slug));
+                            let image = article.images.first().cloned().or(article.cover_image_url.clone());
+                            view! {
+                                <PageMeta
+                                    title=article.title.clone()
+                                    description
+                                    canonical
+                                    image
+                                    published_time=article.published_at.to_rfc3339()
+                                />
+                                <JsonLd data=news_article(&article, outlet.as_ref())/>
+                                <div class="article-container">
+                                    <h1 class="mb-4 text-4xl font-bold text-gray-900">{article.title}</h1>
+                                    <div class="article-content prose" inner_html=content_html></div>
+                                </div>
+                            }
+                            .into_any()
                         }
-                        .into_any()
-                    })
-                    .unwrap_or_else(|| view! { <div><p>"Article not found."</p></div> }.into_any())
-            }}
+                        Ok(None) => view! { <div><p>"Article not found."</p></div> }.into_any(),
+                        Err(_) => view! { <div><p>"The article could not be loaded."</p></div> }.into_any(),
+                    }
+                })}
+            </Suspense>
         </div>
     }
 }
@@ -376,12 +446,187 @@ pub fn ProgrammingPage() -> impl IntoView {
     }
 }
 
+/// Sends `request` and decodes the JSON reply; `Ok(None)` when the server answers 404.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn fetch_json<T: serde::de::DeserializeOwned>(
+    request: gloo_net::http::RequestBuilder,
+) -> Result<Option<T>, String> {
+    let resp = request
+        .send()
+        .await
+        .map_err(|_| "Failed to connect to server".to_string())?;
+    if resp.status() == 404 {
+        return Ok(None);
+    }
+    if !resp.ok() {
+        return Err(crate::components::problem::message(&resp).await);
+    }
+    resp.json()
+        .await
+        .map(Some)
+        .map_err(|_| "Failed to parse response".to_string())
+}
+
+fn blog_date(post: &BlogPost) -> String {
+    post.published_at.format("%B %-d, %Y").to_string()
+}
+
+/// The first paragraph of a post's Markdown, for list previews.
+fn blog_preview(content: &str) -> String {
+    content
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty() && !p.starts_with('#') && !p.starts_with('!'))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Percent-encodes everything but unreserved characters.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[component]
+fn BlogTags(tags: Vec<String>) -> impl IntoView {
+    view! {
+        <div class="blog-tags">
+            {tags
+                .into_iter()
+                .map(|tag| {
+                    let href = format!("/blog?tag={}", encode_query_value(&tag));
+                    view! { <a href=href class="blog-tag">{format!("#{}", tag)}</a> }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
 #[component]
 pub fn PersonalBlogPage() -> impl IntoView {
+    let query = use_query_map();
+    let tag = move || query.with(|q| q.get("tag")).filter(|t| !t.is_empty());
+    let (posts, set_posts) = signal(None::<Vec<BlogPost>>);
+    let (error, set_error) = signal("".to_string());
+
+    // Reloads whenever the tag in the URL changes.
+    #[cfg(target_arch = "wasm32")]
+    Effect::new(move |_| {
+        let tag = tag();
+        set_posts.set(None);
+        set_error.set("".to_string());
+        spawn_local(async move {
+            let request = match tag {
+                Some(tag) => Request::get("/api/blog").query([("tag", tag)]),
+                None => Request::get("/api/blog"),
+            };
+            match fetch_json::<Vec<BlogPost>>(request).await {
+                Ok(list) => set_posts.set(Some(list.unwrap_or_default())),
+                Err(msg) => set_error.set(msg),
+            }
+        });
+    });
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (set_posts, set_error);
+
     view! {
-        <div class="container py-12">
+        <div class="container py-12 max-w-3xl">
             <h1 class="text-4xl mb-6">"Blog"</h1>
-            <p class="text-muted">"Personal thoughts and musings."</p>
+            {move || match tag() {
+                Some(tag) => view! {
+                    <p class="text-muted mb-8">
+                        {format!("Posts tagged #{} · ", tag)}
+                        <a href="/blog">"All posts"</a>
+                    </p>
+                }.into_any(),
+                None => view! { <p class="text-muted mb-8">"Personal thoughts and musings."</p> }.into_any(),
+            }}
+
+            {move || {
+                (!error.get().is_empty()).then(|| view! {
+                    <div class="mb-4 p-3 bg-red-50 border border-red-200 text-red-700 rounded-md text-sm">
+                        {error.get()}
+                    </div>
+                })
+            }}
+
+            {move || match posts.get() {
+                None => view! { <p class="text-muted">"Loading…"</p> }.into_any(),
+                Some(list) if list.is_empty() => view! { <p class="text-muted">"No posts yet."</p> }.into_any(),
+                Some(list) => list
+                    .into_iter()
+                    .map(|post| {
+                        let date = blog_date(&post);
+                        let preview = blog_preview(&post.content);
+                        view! {
+                            <article class="card mb-6">
+                                <p class="journalism-date">{date}</p>
+                                <h2 class="text-2xl font-bold mb-2">
+                                    <a href=format!("/blog/{}", post.slug)>{post.title}</a>
+                                </h2>
+                                <p class="text-gray-700 mb-3">{preview}</p>
+                                <BlogTags tags=post.tags.unwrap_or_default()/>
+                            </article>
+                        }
+                    })
+                    .collect_view()
+                    .into_any(),
+            }}
+        </div>
+    }
+}
+
+#[component]
+pub fn BlogPostPage() -> impl IntoView {
+    let params = use_params_map();
+    let slug = move || params.with(|p| p.get("slug").unwrap_or_default());
+    let post = Resource::new(slug, blog::get_blog_post);
+
+    view! {
+        <div class="container py-12 max-w-3xl">
+            <Suspense fallback=|| view! { <p class="text-muted">"Loading…"</p> }>
+                {move || Suspend::new(async move {
+                    match post.await {
+                        Ok(Some(post)) => {
+                            let date = blog_date(&post);
+                            let description = describe(&to_html(&blog_preview(&post.content)));
+                            let description = if description.is_empty() { post.title.clone() } else { description };
+                            view! {
+                                <PageMeta
+                                    title=post.title.clone()
+                                    description
+                                    canonical=format!("/blog/{}", post.slug)
+                                    image=first_image(&post.content)
+                                    published_time=post.published_at.to_rfc3339()
+                                />
+                                <JsonLd data=blog_posting(&post)/>
+                                <article class="article-container">
+                                    <p class="journalism-date">{date}</p>
+                                    <h1 class="mb-4 text-4xl font-bold text-gray-900">{post.title}</h1>
+                                    <Markdown source=post.content/>
+                                    <BlogTags tags=post.tags.unwrap_or_default()/>
+                                    <a href="/blog" class="mt-8 inline-block">"← All posts"</a>
+                                </article>
+                            }
+                            .into_any()
+                        }
+                        Ok(None) => view! {
+                            <div>
+                                <p>"Post not found."</p>
+                                <a href="/blog">"← All posts"</a>
+                            </div>
+                        }
+                        .into_any(),
+                        Err(_) => view! { <p class="text-muted">"The post could not be loaded."</p> }.into_any(),
+                    }
+                })}
+            </Suspense>
         </div>
     }
 }
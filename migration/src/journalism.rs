@@ -0,0 +1,77 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::path::Path;
+
+pub const DEFAULT_PATH: &str = "migration/data/journalism.json";
+
+/// One article as written by `scripts/import_journalism.py`.
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct JournalismArticle {
+    slug: String,
+    title: String,
+    iso_date: String,
+    display_date: String,
+    source_url: String,
+    content_html: String,
+    images: Vec<String>,
+    excerpt: String,
+}
+
+/// Loads the scraper's output into `articles`. Safe to re-run: rows are
+/// matched by slug and refreshed in place, but only ones this importer
+/// created, so synced and locally written articles are never overwritten.
+pub async fn import(pool: &sqlx::PgPool, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let articles: Vec<JournalismArticle> = serde_json::from_str(&data)?;
+    println!("Found {} articles in {}.", articles.len(), path.display());
+
+    let (mut written, mut skipped) = (0, 0);
+    for article in articles {
+        let published_at = NaiveDate::parse_from_str(&article.iso_date, "%Y-%m-%d")
+            .map_err(|e| format!("{}: bad iso_date {:?}: {}", article.slug, article.iso_date, e))?
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO articles
+                (slug, title, excerpt, content, cover_image_url, author, published_at, origin, source_url, images)
+            VALUES ($1, $2, $3, $4, $5, 'Jake Wray', $6, 'imported', $7, $8)
+            ON CONFLICT (slug) DO UPDATE SET
+                title = EXCLUDED.title,
+                excerpt = EXCLUDED.excerpt,
+                content = EXCLUDED.content,
+                cover_image_url = EXCLUDED.cover_image_url,
+                published_at = EXCLUDED.published_at,
+                source_url = EXCLUDED.source_url,
+                images = EXCLUDED.images,
+                updated_at = NOW()
+            WHERE articles.origin = 'imported'
+            "#,
+        )
+        .bind(&article.slug)
+        .bind(&article.title)
+        .bind(&article.excerpt)
+        .bind(&article.content_html)
+        .bind(article.images.first())
+        .bind(published_at)
+        .bind(&article.source_url)
+        .bind(&article.images)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            println!("Skipping {}: a synced or local article has that slug", article.slug);
+            skipped += 1;
+        } else {
+            println!("Imported: {}", article.title);
+            written += 1;
+        }
+    }
+
+    println!("Imported {} articles, skipped {}.", written, skipped);
+    Ok(())
+}
@@ -2,6 +2,17 @@ use dotenvy::dotenv;
 use serde::Deserialize;
 use sqlx::postgres::PgPoolOptions;
 use std::env;
+use std::path::Path;
+
+mod journalism;
+
+const USAGE: &str = "\
+Usage: migration_tool [command]
+
+Commands:
+  blog                  Import posts from the jakewray.ca WordPress API (default)
+  journalism [path]     Load scripts/import_journalism.py output into articles
+                        (default path: migration/data/journalism.json)";
 
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -22,11 +33,29 @@ struct WpContent {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
+
+    // `None` for the default blog import, or the journalism file to load.
+    let args: Vec<String> = env::args().skip(1).collect();
+    let journalism_path = match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] | ["blog"] => None,
+        ["journalism"] => Some(journalism::DEFAULT_PATH),
+        ["journalism", path] => Some(*path),
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let pool = PgPoolOptions::new().connect(&database_url).await?;
 
-    println!("Importing from jakewray.ca...");
-    import_jakewray(&pool).await?;
+    match journalism_path {
+        Some(path) => journalism::import(&pool, Path::new(path)).await?,
+        None => {
+            println!("Importing from jakewray.ca...");
+            import_jakewray(&pool).await?;
+        }
+    }
 
     Ok(())
 }
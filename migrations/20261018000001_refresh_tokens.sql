@@ -0,0 +1,19 @@
+-- Rotating refresh tokens for bearer-token clients. Each login starts a new
+-- family; every refresh marks the presented token used and issues a successor
+-- in the same family. Presenting a used token revokes the whole family.
+CREATE TABLE refresh_tokens (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id UUID NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX refresh_tokens_user_id_idx ON refresh_tokens (user_id);
+CREATE INDEX refresh_tokens_family_id_idx ON refresh_tokens (family_id);
+
+-- Access tokens issued before this instant are rejected ("log out all devices").
+ALTER TABLE users ADD COLUMN tokens_revoked_before TIMESTAMPTZ;
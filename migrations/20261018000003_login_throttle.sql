@@ -0,0 +1,13 @@
+-- Consecutive failed admin logins per client IP and per username, with the
+-- lockout they have earned. Rows are removed after a successful login (for
+-- usernames) or a day without failures.
+CREATE TABLE login_throttle (
+    scope TEXT NOT NULL,
+    key TEXT NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until TIMESTAMPTZ,
+    PRIMARY KEY (scope, key)
+);
+
+CREATE INDEX login_throttle_last_failure_at_idx ON login_throttle (last_failure_at);
@@ -0,0 +1,7 @@
+-- Admin roles. Accounts that already exist had full access, so they become
+-- owners; new accounts start as viewers unless a role is given.
+ALTER TABLE users
+    ADD COLUMN role TEXT NOT NULL DEFAULT 'owner'
+        CHECK (role IN ('owner', 'editor', 'contributor', 'viewer'));
+
+ALTER TABLE users ALTER COLUMN role SET DEFAULT 'viewer';
@@ -0,0 +1,3 @@
+-- Disabled accounts keep their data but can't sign in, and every way they
+-- were already signed in stops working.
+ALTER TABLE users ADD COLUMN disabled_at TIMESTAMPTZ;
@@ -0,0 +1,3 @@
+-- Keyset pagination walks these listings newest first by (published_at, id).
+CREATE INDEX articles_published_idx ON articles (published_at DESC, id DESC);
+CREATE INDEX blog_posts_published_idx ON blog_posts (published_at DESC, id DESC);
@@ -0,0 +1,5 @@
+-- Where an imported article was first published, and the images it carries
+-- (already rehosted on GCS by scripts/import_journalism.py).
+ALTER TABLE articles
+    ADD COLUMN source_url TEXT,
+    ADD COLUMN images TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,24 @@
+-- Full-text search. Titles weigh most, then subtitles, then excerpts and
+-- synopses, then body text. Markup is stripped so tag and attribute names
+-- don't match.
+ALTER TABLE articles ADD COLUMN search tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', coalesce(subtitle, '')), 'B') ||
+    setweight(to_tsvector('english', coalesce(excerpt, '')), 'C') ||
+    setweight(to_tsvector('english', regexp_replace(content, '<[^>]*>', ' ', 'g')), 'D')
+) STORED;
+
+ALTER TABLE blog_posts ADD COLUMN search tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', regexp_replace(content, '<[^>]*>', ' ', 'g')), 'D')
+) STORED;
+
+ALTER TABLE creative_works ADD COLUMN search tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', title), 'A') ||
+    setweight(to_tsvector('english', coalesce(synopsis, '')), 'C') ||
+    setweight(to_tsvector('english', regexp_replace(coalesce(content, ''), '<[^>]*>', ' ', 'g')), 'D')
+) STORED;
+
+CREATE INDEX articles_search_idx ON articles USING GIN (search);
+CREATE INDEX blog_posts_search_idx ON blog_posts USING GIN (search);
+CREATE INDEX creative_works_search_idx ON creative_works USING GIN (search);
@@ -0,0 +1,11 @@
+-- "Log out all devices" bumps a counter that access tokens carry, instead of
+-- a timestamp compared against their whole-second `iat`, which also rejected
+-- tokens issued in the same second just after the revocation.
+ALTER TABLE users ADD COLUMN token_generation INTEGER NOT NULL DEFAULT 0;
+
+-- Tokens from before this migration carry no generation and count as 0, so
+-- users revoked within an access token's lifetime start at 1 to keep them out.
+UPDATE users SET token_generation = 1
+WHERE tokens_revoked_before > NOW() - INTERVAL '15 minutes';
+
+ALTER TABLE users DROP COLUMN tokens_revoked_before;
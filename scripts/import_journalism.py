@@ -1,7 +1,8 @@
 #!/usr/bin/env python3
 """
 Scrape journalism articles from the Jake Wray WordPress site, download images,
-upload them to GCS, and emit a JSON data file for the frontend.
+upload them to GCS, and emit a JSON data file for the articles importer
+(`cargo run -p migration_tool -- journalism`).
 
 Usage:
   python scripts/import_journalism.py
@@ -35,7 +36,7 @@ BASE_URLS = [
 ]
 BUCKET = "jakewray-portfolio"
 PREFIX = "media/journalism"
-DATA_PATH = Path("frontend/src/data/journalism.json")
+DATA_PATH = Path("migration/data/journalism.json")
 TMP_DIR = Path(".tmp/journalism")
 TMP_DIR.mkdir(parents=True, exist_ok=True)
 
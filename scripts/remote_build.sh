@@ -21,6 +21,9 @@ RUST_LOG=info
 DATABASE_URL=postgres://admin:password@db:5432/portfolio
 EOF
 
+# Versions the backend's content ETags, so a deploy invalidates them.
+GIT_SHA=$(git rev-parse HEAD 2>/dev/null || true)
+
 if [ "$TARGET" = "all" ] || [ "$TARGET" = "backend" ]; then
     echo "Building dependencies image with cache..."
     sudo DOCKER_BUILDKIT=1 docker build \
@@ -49,12 +52,12 @@ fi
 if [ "$TARGET" = "all" ]; then
     echo "Building and starting ALL services with BuildKit caching..."
     sudo DOCKER_BUILDKIT=1 docker compose -f docker-compose.prod.yml build \
-        --build-arg BUILDKIT_INLINE_CACHE=1
+        --build-arg BUILDKIT_INLINE_CACHE=1 --build-arg GIT_SHA="$GIT_SHA"
     sudo docker compose -f docker-compose.prod.yml up -d --remove-orphans
 elif [ "$TARGET" = "backend" ]; then
     echo "Building and restarting BACKEND (portfolio) service with caching..."
     sudo DOCKER_BUILDKIT=1 docker compose -f docker-compose.prod.yml build \
-        --build-arg BUILDKIT_INLINE_CACHE=1 portfolio
+        --build-arg BUILDKIT_INLINE_CACHE=1 --build-arg GIT_SHA="$GIT_SHA" portfolio
     sudo docker compose -f docker-compose.prod.yml up -d --no-deps portfolio
 elif [ "$TARGET" = "frontend" ]; then
     echo "Frontend is part of the backend binary in this setup (SSR)."
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum Origin {
     Imported,
     Synced,
@@ -10,6 +11,7 @@ pub enum Origin {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Article {
     pub id: Uuid,
     pub wp_id: Option<i64>,
@@ -22,9 +24,38 @@ pub struct Article {
     pub author: String,
     pub published_at: DateTime<Utc>,
     pub origin: Origin,
+    /// Where the article was first published, for imported articles.
+    pub source_url: Option<String>,
+    /// Every image in `content`, in order.
+    pub images: Vec<String>,
+}
+
+impl Origin {
+    pub const ALL: [Origin; 3] = [Origin::Imported, Origin::Synced, Origin::Local];
+
+    /// The value stored in `articles.origin`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Origin::Imported => "imported",
+            Origin::Synced => "synced",
+            Origin::Local => "local",
+        }
+    }
+}
+
+impl std::str::FromStr for Origin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Origin::ALL
+            .into_iter()
+            .find(|origin| origin.as_str() == s)
+            .ok_or_else(|| format!("Unknown origin: {}", s))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct BlogPost {
     pub id: Uuid,
     pub slug: String,
@@ -34,7 +65,42 @@ pub struct BlogPost {
     pub tags: Option<Vec<String>>,
 }
 
+/// What kind of content a [`SearchResult`] points at.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SearchKind {
+    Article,
+    BlogPost,
+    CreativeWork,
+}
+
+/// One hit from `/api/search`, best matches first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub slug: String,
+    pub title: String,
+    /// Matching passages as HTML, with the matched words in `<mark>`.
+    pub snippet: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub rank: f32,
+}
+
+impl SearchResult {
+    /// The page showing this result, if it has one yet.
+    pub fn url(&self) -> Option<String> {
+        match self.kind {
+            SearchKind::Article => Some(format!("/journalism/{}", self.slug)),
+            SearchKind::BlogPost => Some(format!("/blog/{}", self.slug)),
+            SearchKind::CreativeWork => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum MediaCategory {
     Photography,
     VisualArt,
@@ -43,12 +109,14 @@ pub enum MediaCategory {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum MediaContext {
     Personal,
     Professional,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct MediaItem {
     pub id: Uuid,
     pub title: Option<String>,
@@ -61,6 +129,7 @@ pub struct MediaItem {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum CreativeType {
     Story,
     Novel,
@@ -68,6 +137,7 @@ pub enum CreativeType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreativeWork {
     pub id: Uuid,
     pub slug: String,
@@ -80,6 +150,7 @@ pub struct CreativeWork {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Project {
     pub id: Uuid,
     pub name: String,
@@ -95,3 +166,144 @@ pub struct Project {
 pub struct ModelCount {
     pub count: i64,
 }
+
+/// An admin user's role, stored as lowercase text in `users.role`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Owner,
+    Editor,
+    Contributor,
+    Viewer,
+}
+
+/// Something an admin endpoint can require.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Create and edit unpublished blog posts.
+    DraftPosts,
+    /// Publish, unpublish and delete any blog post.
+    PublishPosts,
+    UploadMedia,
+    DeleteMedia,
+    EditArticles,
+    DeleteArticles,
+    RunSync,
+    ManageUsers,
+    ViewAuditLog,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Owner, Role::Editor, Role::Contributor, Role::Viewer];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::Editor => "editor",
+            Role::Contributor => "contributor",
+            Role::Viewer => "viewer",
+        }
+    }
+
+    /// The permission matrix. Viewers can only look around the admin area.
+    pub fn allows(self, permission: Permission) -> bool {
+        use Permission::*;
+        match self {
+            Role::Owner => true,
+            Role::Editor => !matches!(permission, ManageUsers | ViewAuditLog),
+            Role::Contributor => matches!(permission, DraftPosts | UploadMedia),
+            Role::Viewer => false,
+        }
+    }
+}
+
+impl Permission {
+    pub const ALL: [Permission; 9] = [
+        Permission::DraftPosts,
+        Permission::PublishPosts,
+        Permission::UploadMedia,
+        Permission::DeleteMedia,
+        Permission::EditArticles,
+        Permission::DeleteArticles,
+        Permission::RunSync,
+        Permission::ManageUsers,
+        Permission::ViewAuditLog,
+    ];
+
+    /// The API token scope that grants this permission, e.g. `sync:run`.
+    pub fn scope(self) -> &'static str {
+        match self {
+            Permission::DraftPosts => "posts:draft",
+            Permission::PublishPosts => "posts:publish",
+            Permission::UploadMedia => "media:upload",
+            Permission::DeleteMedia => "media:delete",
+            Permission::EditArticles => "articles:write",
+            Permission::DeleteArticles => "articles:delete",
+            Permission::RunSync => "sync:run",
+            Permission::ManageUsers => "users:manage",
+            Permission::ViewAuditLog => "audit:read",
+        }
+    }
+
+    /// Parses a scope name back into its permission.
+    pub fn from_scope(scope: &str) -> Option<Permission> {
+        Permission::ALL.into_iter().find(|p| p.scope() == scope)
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("Unknown role: {}", s))
+    }
+}
+
+impl TryFrom<String> for Role {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Cookie holding the double-submit CSRF token. Readable by scripts so the
+/// client can echo it back; it grants nothing on its own.
+pub const CSRF_COOKIE: &str = "csrf_token";
+/// Form field that carries the CSRF token in form posts.
+pub const CSRF_FIELD: &str = "csrf_token";
+/// Header that carries the CSRF token for scripted requests.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// The current request's CSRF token, put in the request extensions by the
+/// backend so server-rendered forms can embed it.
+#[derive(Debug, Clone)]
+pub struct CsrfToken(pub String);
+
+/// Header carrying the request's correlation id, echoed on every response.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// An API error body, served as `application/problem+json` (RFC 9457).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Problem {
+    /// Always `about:blank`; `code` identifies the error.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The HTTP status phrase.
+    pub title: String,
+    pub status: u16,
+    /// Human-readable explanation, safe to show to users.
+    pub detail: String,
+    /// Stable machine-readable code such as `not_found` or `invalid_cursor`.
+    pub code: String,
+    /// Matches the `x-request-id` response header and the server logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}